@@ -0,0 +1,242 @@
+//! Burn-reason proofs
+//!
+//! A burner can show a third party, e.g. a recycling reward service, that one
+//! of their burns had a given reason without saying which token it was. After
+//! burning, the burner publishes `blind = H(secret, 0)` and the contract
+//! records `commitment = H(H(product, blind), reason)`, where `H` is the MiMC
+//! compression below. A Groth16 proof then shows knowledge of a product and a
+//! secret opening a recorded commitment to the claimed reason; its only public
+//! inputs are the commitment and the reason.
+
+use super::*;
+use crate::verification::{field_to_scalar, scalar_to_field, Field};
+use bellman::groth16::{self, Parameters};
+use bellman::{Circuit, ConstraintSystem, SynthesisError, Variable};
+use bls12_381::{Bls12, Scalar};
+use sha2::Sha512;
+
+/// MiMC rounds for 128-bit security with x^3 over the BLS12-381 scalar field
+const MIMC_ROUNDS: u64 = 322;
+
+/// MiMC round constants, derived from a fixed tag so provers and the
+/// contract agree on them
+fn mimc_constants() -> Vec<Scalar> {
+    (0..MIMC_ROUNDS)
+        .map(|round| {
+            let mut hasher = Sha512::new();
+            hasher.update(b"veri-charm/mimc");
+            hasher.update(round.to_be_bytes());
+            let mut wide = [0u8; 64];
+            wide.copy_from_slice(&hasher.finalize());
+            Scalar::from_bytes_wide(&wide)
+        })
+        .collect()
+}
+
+/// MiMC compression of two field elements (x^3 Feistel network)
+fn mimc(mut xl: Scalar, mut xr: Scalar) -> Scalar {
+    for constant in mimc_constants() {
+        let shifted = xl + constant;
+        let next = shifted.square() * shifted + xr;
+        xr = xl;
+        xl = next;
+    }
+    xl
+}
+
+/// Scalar for a hash, reduced modulo the field order
+fn hash_scalar(hash: &Hash) -> Scalar {
+    let mut wide = [0u8; 64];
+    wide[..32].copy_from_slice(hash);
+    Scalar::from_bytes_wide(&wide)
+}
+
+/// Private circuit input standing for a product
+fn product_scalar(product_id: &ProductId) -> Scalar {
+    hash_scalar(&crypto::canonical_hash(&[b"veri-charm/burn-product", product_id.as_bytes()]))
+}
+
+fn reason_scalar(reason: &BurnReason) -> Scalar {
+    Scalar::from(reason.code())
+}
+
+/// Public input encoding of `reason`
+pub fn reason_field(reason: &BurnReason) -> Field {
+    scalar_to_field(&reason_scalar(reason))
+}
+
+fn commit(product: Scalar, blind: Scalar, reason: Scalar) -> Scalar {
+    mimc(mimc(product, blind), reason)
+}
+
+/// Blinding value a burner passes to `commit_burn_reason` for `secret`
+pub fn burn_blind(secret: &Hash) -> Field {
+    scalar_to_field(&mimc(hash_scalar(secret), Scalar::zero()))
+}
+
+/// Commitment to a burn of `product_id` for `reason` under `blind`, or
+/// `None` if `blind` is not a field element
+pub fn burn_commitment(product_id: &ProductId, reason: &BurnReason, blind: &Field) -> Option<Field> {
+    let blind = field_to_scalar(blind)?;
+    Some(scalar_to_field(&commit(product_scalar(product_id), blind, reason_scalar(reason))))
+}
+
+/// Knowledge of a product and secret opening a commitment to a reason.
+/// Public inputs, in order: the commitment, the reason. Parameters are
+/// generated from the default (empty) circuit.
+#[derive(Clone, Debug, Default)]
+pub struct BurnReasonCircuit {
+    pub product: Option<Scalar>,
+    pub secret: Option<Scalar>,
+    pub reason: Option<Scalar>,
+}
+
+impl Circuit<Scalar> for BurnReasonCircuit {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        let constants = mimc_constants();
+        let commitment_value = self.product.zip(self.secret).zip(self.reason)
+            .map(|((product, secret), reason)| commit(product, mimc(secret, Scalar::zero()), reason));
+
+        let commitment = cs.alloc_input(
+            || "commitment",
+            || commitment_value.ok_or(SynthesisError::AssignmentMissing),
+        )?;
+        let reason = cs.alloc_input(|| "reason", || self.reason.ok_or(SynthesisError::AssignmentMissing))?;
+        let product = cs.alloc(|| "product", || self.product.ok_or(SynthesisError::AssignmentMissing))?;
+        let secret = cs.alloc(|| "secret", || self.secret.ok_or(SynthesisError::AssignmentMissing))?;
+        let zero = cs.alloc(|| "zero", || Ok(Scalar::zero()))?;
+        cs.enforce(|| "zero is zero", |lc| lc + zero, |lc| lc + CS::one(), |lc| lc);
+
+        let blind = mimc_gadget(
+            cs.namespace(|| "blind"),
+            &constants,
+            (secret, self.secret),
+            (zero, Some(Scalar::zero())),
+        )?;
+        let inner = mimc_gadget(cs.namespace(|| "product"), &constants, (product, self.product), blind)?;
+        let (opened, _) = mimc_gadget(cs.namespace(|| "reason"), &constants, inner, (reason, self.reason))?;
+        cs.enforce(
+            || "opens the commitment",
+            |lc| lc + opened,
+            |lc| lc + CS::one(),
+            |lc| lc + commitment,
+        );
+        Ok(())
+    }
+}
+
+/// Constrain MiMC over two allocated inputs; returns the output and its value
+fn mimc_gadget<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    constants: &[Scalar],
+    (mut xl, mut xl_value): (Variable, Option<Scalar>),
+    (mut xr, mut xr_value): (Variable, Option<Scalar>),
+) -> Result<(Variable, Option<Scalar>), SynthesisError> {
+    for (round, constant) in constants.iter().enumerate() {
+        let mut cs = cs.namespace(|| format!("round {}", round));
+
+        // squared = (xL + c)^2
+        let squared_value = xl_value.map(|value| (value + constant).square());
+        let squared = cs.alloc(|| "squared", || squared_value.ok_or(SynthesisError::AssignmentMissing))?;
+        cs.enforce(
+            || "squared = (xL + c)^2",
+            |lc| lc + xl + (*constant, CS::one()),
+            |lc| lc + xl + (*constant, CS::one()),
+            |lc| lc + squared,
+        );
+
+        // next = xR + (xL + c)^3
+        let next_value = xl_value.zip(squared_value).zip(xr_value)
+            .map(|((left, squared), right)| (left + constant) * squared + right);
+        let next = cs.alloc(|| "next", || next_value.ok_or(SynthesisError::AssignmentMissing))?;
+        cs.enforce(
+            || "next = xR + (xL + c)^3",
+            |lc| lc + squared,
+            |lc| lc + xl + (*constant, CS::one()),
+            |lc| lc + next - xr,
+        );
+
+        xr = xl;
+        xr_value = xl_value;
+        xl = next;
+        xl_value = next_value;
+    }
+    Ok((xl, xl_value))
+}
+
+/// Prove that the burn behind `receipt` was for `reason`, with `secret` the
+/// one whose `burn_blind` was committed. `params` are the circuit's proving
+/// parameters. Proof randomness is derived from the secret, so proving the
+/// same statement twice gives the same proof.
+pub fn prove_burn_reason(
+    params: &Parameters<Bls12>,
+    receipt: &BurnReceipt,
+    reason: &BurnReason,
+    secret: &Hash,
+) -> Result<ZkProof, VeriCharmError> {
+    let circuit = BurnReasonCircuit {
+        product: Some(product_scalar(&receipt.product_id)),
+        secret: Some(hash_scalar(secret)),
+        reason: Some(reason_scalar(reason)),
+    };
+    let randomness = |tag: &[u8]| {
+        hash_scalar(&crypto::canonical_hash(&[
+            tag,
+            secret,
+            receipt.product_id.as_bytes(),
+            &reason.code().to_be_bytes(),
+        ]))
+    };
+
+    let proof = groth16::create_proof(
+        circuit,
+        params,
+        randomness(b"veri-charm/burn-proof-r"),
+        randomness(b"veri-charm/burn-proof-s"),
+    ).map_err(|_| VeriCharmError::InvalidProof)?;
+    let mut proof_bytes = Vec::new();
+    proof.write(&mut proof_bytes).map_err(|_| VeriCharmError::MalformedProof)?;
+    Ok(ZkProof { proof_bytes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bellman::gadgets::test::TestConstraintSystem;
+
+    fn circuit(reason: &BurnReason) -> BurnReasonCircuit {
+        BurnReasonCircuit {
+            product: Some(product_scalar(&"VC-1".into())),
+            secret: Some(hash_scalar(&[7u8; 32])),
+            reason: Some(reason_scalar(reason)),
+        }
+    }
+
+    #[test]
+    fn circuit_opens_the_commitment_recorded_on_chain() {
+        let commitment = burn_commitment(&"VC-1".into(), &BurnReason::Voluntary, &burn_blind(&[7u8; 32])).unwrap();
+        let inputs = [field_to_scalar(&commitment).unwrap(), reason_scalar(&BurnReason::Voluntary)];
+
+        let mut cs = TestConstraintSystem::new();
+        circuit(&BurnReason::Voluntary).synthesize(&mut cs).unwrap();
+        assert!(cs.is_satisfied());
+        assert!(cs.verify(&inputs));
+
+        // A witness for another reason opens a different commitment
+        let mut cs = TestConstraintSystem::new();
+        circuit(&BurnReason::RaffleEntry).synthesize(&mut cs).unwrap();
+        assert!(cs.is_satisfied());
+        assert!(!cs.verify(&inputs));
+        assert!(!cs.verify(&[inputs[0], reason_scalar(&BurnReason::RaffleEntry)]));
+    }
+
+    #[test]
+    fn commitments_hide_the_product_behind_the_blind() {
+        let blind = burn_blind(&[7u8; 32]);
+        let commitment = burn_commitment(&"VC-1".into(), &BurnReason::Voluntary, &blind);
+        assert_ne!(commitment, burn_commitment(&"VC-2".into(), &BurnReason::Voluntary, &blind));
+        assert_ne!(commitment, burn_commitment(&"VC-1".into(), &BurnReason::Voluntary, &burn_blind(&[8u8; 32])));
+        assert_ne!(commitment, burn_commitment(&"VC-1".into(), &BurnReason::Expired, &blind));
+        assert_eq!(burn_commitment(&"VC-1".into(), &BurnReason::Voluntary, &[0xff; 32]), None);
+    }
+}
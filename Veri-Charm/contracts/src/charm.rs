@@ -17,71 +17,331 @@ pub struct CharmToken {
     pub current_owner: Address,
     /// Block height when minted
     pub mint_time: u64,
-    /// Warranty period in seconds
+    /// Warranty period in blocks
     pub warranty_period: u64,
+    /// Units `warranty_period` was stored in; 0 for tokens minted before
+    /// warranty periods were converted from seconds to blocks
+    #[serde(default)]
+    pub warranty_schema: u16,
     /// Whether token has been burned
     pub burned: bool,
     /// Transfer history
     pub transfer_history: Vec<TransferRecord>,
     /// Optional ZK proof for privacy
     pub zk_proof: Option<ZkProof>,
+    /// Block of the last recorded successful verification
+    #[serde(default)]
+    pub last_verified_block: Option<u64>,
+    /// Remaining quantity for bulk tokens; `None` for individual items
+    #[serde(default)]
+    pub quantity: Option<u64>,
+    /// Number of child tokens split off by `transfer_quantity`
+    #[serde(default)]
+    pub split_count: u64,
+    /// Parent token this one was split from
+    #[serde(default)]
+    pub split_from: Option<ProductId>,
+    /// Block after which a perishable product is expired
+    #[serde(default)]
+    pub expiry_block: Option<u64>,
+    /// Frozen pending manufacturer review; frozen tokens cannot move
+    #[serde(default)]
+    pub frozen: bool,
+    /// Whether this token's warranty liability has been released
+    #[serde(default)]
+    pub liability_released: bool,
+    /// Hash chain over transfer records rolled out of `transfer_history`
+    #[serde(default)]
+    pub history_root: Option<Hash>,
+    /// Number of transfer records folded into `history_root`
+    #[serde(default)]
+    pub archived_transfers: u64,
+    /// Additional addresses authorized to transfer or burn alongside the owner
+    #[serde(default)]
+    pub co_owners: Vec<Address>,
+    /// Token this one replaced via `reissue_token`
+    #[serde(default)]
+    pub reissued_from: Option<ProductId>,
+    /// Production batch, derived from the manufacturer and `product_data.batch_id`
+    #[serde(default)]
+    pub batch_id: Option<Hash>,
+    /// Whether the product has been recalled
+    #[serde(default)]
+    pub recalled: bool,
+    /// Manufacturer-supplied recall reason
+    #[serde(default)]
+    pub recall_reason: Option<String>,
+    /// Owner's chosen visibility; reset to public on every ownership change
+    #[serde(default)]
+    pub owner_privacy: PrivacyLevel,
+    /// Permanently barred from moving
+    #[serde(default)]
+    pub blacklisted: bool,
+    /// Block warranty coverage started at, if started on sale rather than mint
+    #[serde(default)]
+    pub warranty_start_block: Option<u64>,
+    /// Block of the last resale that restarted the warranty, if any
+    #[serde(default)]
+    pub warranty_restart: Option<u64>,
+    /// Active beam holding the token; it cannot move or burn until cleared
+    #[serde(default)]
+    pub locked_beam: Option<BeamId>,
+    /// Superseded metadata versions with the block each was replaced at
+    #[serde(default)]
+    pub metadata_history: Vec<(u64, TokenMetadata)>,
+    /// Tokens grouped under this one by `bundle`
+    #[serde(default)]
+    pub children: Vec<ProductId>,
+    /// Bundle holding this token; it cannot move on its own until unbundled
+    #[serde(default)]
+    pub parent: Option<ProductId>,
+    /// Printable serial with check symbol, assigned at mint
+    #[serde(default)]
+    pub serial: String,
+    /// Authorized distribution route: the registered holders the first hops
+    /// must go to, in order. Empty means any route.
+    #[serde(default)]
+    pub expected_roles: Vec<Address>,
 }
 
 impl CharmToken {
+    /// New token owned by its manufacturer with empty history
+    pub fn new(
+        id: ProductId,
+        manufacturer: Address,
+        product_data: ProductData,
+        metadata: TokenMetadata,
+        mint_time: u64,
+        warranty_period: u64,
+    ) -> Self {
+        let batch_id = Self::derive_batch_id(&manufacturer, &product_data.batch_id);
+        let genesis = TransferRecord::genesis(manufacturer.clone(), mint_time);
+        CharmToken {
+            id,
+            current_owner: manufacturer.clone(),
+            manufacturer,
+            quantity: product_data.quantity,
+            expiry_block: product_data.expiry_block,
+            product_data,
+            metadata,
+            mint_time,
+            warranty_period,
+            warranty_schema: WARRANTY_SCHEMA_VERSION,
+            burned: false,
+            transfer_history: vec![genesis],
+            zk_proof: None,
+            last_verified_block: None,
+            split_count: 0,
+            split_from: None,
+            frozen: false,
+            liability_released: false,
+            history_root: None,
+            archived_transfers: 0,
+            co_owners: Vec::new(),
+            reissued_from: None,
+            batch_id,
+            recalled: false,
+            recall_reason: None,
+            owner_privacy: PrivacyLevel::Public,
+            blacklisted: false,
+            warranty_start_block: None,
+            warranty_restart: None,
+            locked_beam: None,
+            metadata_history: Vec::new(),
+            children: Vec::new(),
+            parent: None,
+            serial: String::new(),
+            expected_roles: Vec::new(),
+        }
+    }
+    
+    /// Batch hash for a manufacturer's production batch label
+    pub fn derive_batch_id(manufacturer: &Address, batch_label: &str) -> Option<Hash> {
+        if batch_label.is_empty() {
+            return None;
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(manufacturer);
+        hasher.update(batch_label.as_bytes());
+        Some(hasher.finalize().into())
+    }
+    
     /// Verify if token is still within warranty period
     pub fn is_in_warranty(&self, current_time: u64) -> bool {
-        current_time < self.mint_time + self.warranty_period
+        current_time < self.warranty_expiry()
     }
     
-    /// Verify supply chain integrity
-    pub fn verify_supply_chain(&self) -> bool {
-        // Must have at least manufacturer mint and one transfer
-        if self.transfer_history.len() < 2 {
-            return false;
-        }
-        
-        // First transfer must be from manufacturer
-        if self.transfer_history[0].from != self.manufacturer {
-            return false;
+    /// Block warranty coverage counts from: the last restarting resale, else
+    /// the sale start if set, else mint
+    pub fn warranty_start(&self) -> u64 {
+        self.warranty_restart
+            .or(self.warranty_start_block)
+            .unwrap_or(self.mint_time)
+    }
+    
+    /// Time at which warranty coverage ends
+    pub fn warranty_expiry(&self) -> u64 {
+        self.warranty_start() + self.warranty_period
+    }
+    
+    /// Warranty coverage left at `current_time`, zero once expired
+    pub fn warranty_remaining(&self, current_time: u64) -> u64 {
+        self.warranty_expiry().saturating_sub(current_time)
+    }
+    
+    /// Average blocks each owner held the token, counting the current owner's
+    /// still-open holding period up to `current_time`. Holding periods are
+    /// consecutive, so this is the span since mint over the number of owners.
+    pub fn average_holding_blocks(&self, current_time: u64) -> u64 {
+        // The genesis record is the first one archived, and isn't a handoff
+        let archived_handoffs = self.archived_transfers.saturating_sub(1);
+        let handoffs = self.transfer_history.iter().filter(|record| !record.is_genesis()).count();
+        let owners = archived_handoffs + handoffs as u64 + 1;
+        current_time.saturating_sub(self.mint_time) / owners
+    }
+    
+    /// Whether `address` is the owner or a co-owner
+    pub fn is_owned_by(&self, address: &Address) -> bool {
+        self.current_owner == *address || self.co_owners.contains(address)
+    }
+    
+    /// Copy with the owner, co-owners and transfer history cleared, for
+    /// viewers the owner's privacy level hides them from
+    pub fn redacted(&self) -> Self {
+        CharmToken {
+            current_owner: Address::default(),
+            co_owners: Vec::new(),
+            transfer_history: Vec::new(),
+            ..self.clone()
         }
+    }
+    
+    /// Whether a perishable product is past its expiry block
+    pub fn is_expired(&self, current_time: u64) -> bool {
+        self.expiry_block.is_some_and(|expiry| current_time > expiry)
+    }
+    
+    /// Verify supply chain integrity.
+    ///
+    /// Fails once history has been rolled up; use
+    /// `verify_supply_chain_with_proof` with the archived prefix instead.
+    pub fn verify_supply_chain(&self) -> bool {
+        self.history_root.is_none()
+            && verification::is_continuous_chain(
+                &self.manufacturer,
+                &self.current_owner,
+                &[],
+                &self.transfer_history,
+            )
+    }
+    
+    /// Whether the token followed `expected_roles`: hop `n` after mint went to
+    /// the `n`-th expected holder. Hops past the route (e.g. to consumers) are
+    /// unrestricted, as are archived hops, which were checked when live.
+    pub fn verify_route(&self) -> bool {
+        // The genesis record is the first one archived
+        let archived_hops = self.archived_transfers.saturating_sub(1) as usize;
+        let hops = self.transfer_history.iter().filter(|record| !record.is_genesis());
+        self.expected_roles
+            .iter()
+            .skip(archived_hops)
+            .zip(hops)
+            .all(|(expected, hop)| hop.to == *expected)
+    }
+    
+    /// Verify supply chain integrity across an archived prefix and the live history.
+    /// The prefix must hash to the stored `history_root`.
+    pub fn verify_supply_chain_with_proof(
+        &self,
+        provenance_proof: &[TransferRecord],
+    ) -> Result<bool, VeriCharmError> {
+        verification::verify_provenance(
+            &self.manufacturer,
+            &self.current_owner,
+            self.history_root,
+            self.archived_transfers,
+            provenance_proof,
+            &self.transfer_history,
+        )
+    }
+    
+    /// Fold all but the last `keep_last` transfer records into `history_root`
+    pub fn rollup_history(&mut self, keep_last: usize) -> usize {
+        let archive_count = self.transfer_history.len().saturating_sub(keep_last);
+        let archived: Vec<TransferRecord> = self.transfer_history.drain(..archive_count).collect();
         
-        // Check for continuous ownership chain
-        let mut current_owner = self.manufacturer.clone();
-        
-        for transfer in &self.transfer_history {
-            if transfer.from != current_owner {
-                return false;
-            }
-            current_owner = transfer.to.clone();
+        if !archived.is_empty() {
+            let root = self.history_root.unwrap_or([0u8; 32]);
+            self.history_root = Some(verification::extend_history_root(root, &archived));
+            self.archived_transfers += archived.len() as u64;
         }
-        
-        true
+        archived.len()
     }
     
-    /// Generate verification data for ZK proof
-    pub fn generate_verification_data(&self) -> VerificationData {
+    /// Generate verification data for ZK proof at `block_height`, which is
+    /// also the block a tag scan is challenged with
+    pub fn generate_verification_data(&self, block_height: u64) -> VerificationData {
         VerificationData {
             product_id: self.id.clone(),
             manufacturer: self.manufacturer.clone(),
             current_owner: self.current_owner.clone(),
             mint_time: self.mint_time,
-            warranty_valid: self.is_in_warranty(get_current_block_height()),
+            warranty_valid: self.is_in_warranty(block_height),
             // Additional verification inputs
             verification_hash: self.calculate_verification_hash(),
+            zk_proof: self.zk_proof.clone(),
+            public_inputs: Vec::new(),
+            nullifier: None,
+            tag_signature: None,
+            tag_challenge_block: block_height,
+            manufacturer_proof: None,
+            manufacturer_public_inputs: Vec::new(),
+            provenance_proof: Vec::new(),
         }
     }
     
-    /// Calculate verification hash
-    fn calculate_verification_hash(&self) -> Hash {
-        let mut hasher = Sha256::new();
-        hasher.update(&self.id);
-        hasher.update(&self.manufacturer);
-        hasher.update(&self.mint_time.to_be_bytes());
-        hasher.update(&self.current_owner);
-        hasher.finalize().into()
+    /// Content hash over the token's canonical state: identity, ownership,
+    /// product data, metadata and transfer count. Structs are JSON-encoded in
+    /// declaration order, so the encoding is deterministic and any edit to
+    /// them changes the hash.
+    pub fn calculate_verification_hash(&self) -> Hash {
+        let product_data = serde_json::to_vec(&self.product_data).unwrap_or_default();
+        let metadata = serde_json::to_vec(&self.metadata).unwrap_or_default();
+        let transfer_count = self.archived_transfers + self.transfer_history.len() as u64;
+        
+        crypto::canonical_hash(&[
+            b"veri-charm/token-state",
+            self.id.as_bytes(),
+            self.manufacturer.as_ref(),
+            &self.mint_time.to_be_bytes(),
+            self.current_owner.as_ref(),
+            &product_data,
+            &metadata,
+            &transfer_count.to_be_bytes(),
+        ])
     }
 }
 
+/// Encode a token for beaming: a 2-byte big-endian payload version followed
+/// by the JSON-serialized token
+pub fn encode_beam_payload(charm_token: &CharmToken) -> Vec<u8> {
+    let mut payload = BEAM_PAYLOAD_VERSION.to_be_bytes().to_vec();
+    payload.extend(serde_json::to_vec(charm_token).unwrap_or_default());
+    payload
+}
+
+/// Decode a beamed token, rejecting payload versions this contract can't read
+pub fn decode_beam_payload(payload: &[u8]) -> Result<CharmToken, VeriCharmError> {
+    if payload.len() < 2 {
+        return Err(VeriCharmError::InvalidBeamPayload);
+    }
+    let version = u16::from_be_bytes([payload[0], payload[1]]);
+    if version != BEAM_PAYLOAD_VERSION {
+        return Err(VeriCharmError::IncompatiblePayloadVersion);
+    }
+    serde_json::from_slice(&payload[2..]).map_err(|_| VeriCharmError::InvalidBeamPayload)
+}
+
 /// Transfer record for provenance tracking
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct TransferRecord {
@@ -91,13 +351,94 @@ pub struct TransferRecord {
     pub tx_hash: Hash,
 }
 
-/// Burn reasons
+impl TransferRecord {
+    /// Mint record moving a new token from the zero address to its manufacturer
+    pub fn genesis(manufacturer: Address, mint_time: u64) -> Self {
+        TransferRecord {
+            from: Address::default(),
+            to: manufacturer,
+            timestamp: mint_time,
+            tx_hash: [0u8; 32],
+        }
+    }
+
+    /// Whether this is a mint record from the zero address
+    pub fn is_genesis(&self) -> bool {
+        self.from == Address::default()
+    }
+
+    /// Hash of the record's fields
+    pub fn record_hash(&self) -> Hash {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.from);
+        hasher.update(&self.to);
+        hasher.update(&self.timestamp.to_be_bytes());
+        hasher.update(&self.tx_hash);
+        hasher.finalize().into()
+    }
+}
+
+/// Receipt returned by `transfer_charm`, including the warranty the new owner inherits
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TransferReceipt {
+    pub product_id: ProductId,
+    pub from: Address,
+    pub to: Address,
+    pub transfer_time: u64,
+    /// Warranty coverage left after the transfer
+    pub warranty_remaining_blocks: u64,
+    pub warranty_transferable: bool,
+    /// Warranty status after the transfer
+    pub warranty_active: bool,
+}
+
+/// Transfer offer awaiting the recipient's acceptance
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PendingTransfer {
+    pub product_id: ProductId,
+    pub from: Address,
+    pub to: Address,
+    pub offered_at: u64,
+    pub zk_proof: Option<ZkProof>,
+    /// Nullifier and last valid block of the oracle attestation gating this
+    /// offer, spent on acceptance
+    #[serde(default)]
+    pub attestation: Option<(Hash, u64)>,
+}
+
+/// Result of `transfer_charm`: either completed, or held as an offer for the
+/// recipient to accept
 #[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum TransferOutcome {
+    Completed(TransferReceipt),
+    Offered(PendingTransfer),
+}
+
+/// Burn reasons
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
 pub enum BurnReason {
+    /// Also the reason of receipts stored before reasons were recorded,
+    /// when raffle entry was the only burn
+    #[default]
     RaffleEntry,
     ProductReturn,
     WarrantyClaim,
     Voluntary,
+    /// Perishable past its `expiry_block`; may be burned by anyone
+    Expired,
+}
+
+impl BurnReason {
+    /// Stable code of the reason, used as a circuit public input
+    pub fn code(&self) -> u64 {
+        match self {
+            BurnReason::RaffleEntry => 1,
+            BurnReason::ProductReturn => 2,
+            BurnReason::WarrantyClaim => 3,
+            BurnReason::Voluntary => 4,
+            BurnReason::Expired => 5,
+        }
+    }
 }
 
 /// Burn receipt
@@ -106,6 +447,8 @@ pub struct BurnReceipt {
     pub product_id: ProductId,
     pub burner: Address,
     pub burn_time: u64,
+    #[serde(default)]
+    pub reason: BurnReason,
     pub raffle_entry: Option<RaffleEntry>,
 }
 
@@ -116,4 +459,7 @@ pub struct RaffleEntry {
     pub product_id: ProductId,
     pub burn_time: u64,
     pub entry_id: Hash,
+    /// Relative chance of winning a draw
+    #[serde(default)]
+    pub weight: u64,
 }
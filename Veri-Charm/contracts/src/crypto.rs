@@ -0,0 +1,218 @@
+//! Signature helpers (BIP-340 Schnorr over secp256k1)
+
+use super::*;
+use k256::schnorr::{signature::Verifier, Signature as SchnorrSignature, VerifyingKey};
+
+/// 32-byte x-only public key
+pub type PublicKey = Vec<u8>;
+/// 64-byte Schnorr signature
+pub type Signature = Vec<u8>;
+
+/// Verify `signature` over `message` by `public_key`
+pub fn verify_signature(public_key: &PublicKey, message: &[u8], signature: &Signature) -> bool {
+    let Ok(key) = VerifyingKey::from_bytes(public_key) else {
+        return false;
+    };
+    let Ok(signature) = SchnorrSignature::try_from(signature.as_slice()) else {
+        return false;
+    };
+    key.verify(message, &signature).is_ok()
+}
+
+/// Context every signed operation is bound to, so a signature made for one
+/// chain, deployment or manufacturer cannot be replayed in another
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SigningDomain {
+    pub chain_id: ChainId,
+    pub contract: Address,
+    pub manufacturer: Address,
+}
+
+impl SigningDomain {
+    /// Digest a signer must sign for `payload` using their current `nonce`
+    pub fn message(&self, nonce: u64, payload: &[u8]) -> Hash {
+        let mut hasher = Sha256::new();
+        update_field(&mut hasher, b"veri-charm/signed-op");
+        update_field(&mut hasher, self.chain_id.as_bytes());
+        update_field(&mut hasher, self.contract.as_ref());
+        update_field(&mut hasher, self.manufacturer.as_ref());
+        update_field(&mut hasher, &nonce.to_be_bytes());
+        update_field(&mut hasher, payload);
+        hasher.finalize().into()
+    }
+
+    /// Digest a manufacturer signs for product data. Carries no nonce, so
+    /// products can be signed ahead of minting and in any order; each digest
+    /// is accepted once.
+    pub fn product_message(&self, payload: &[u8]) -> Hash {
+        let mut hasher = Sha256::new();
+        update_field(&mut hasher, b"veri-charm/product-data");
+        update_field(&mut hasher, self.chain_id.as_bytes());
+        update_field(&mut hasher, self.contract.as_ref());
+        update_field(&mut hasher, self.manufacturer.as_ref());
+        update_field(&mut hasher, payload);
+        hasher.finalize().into()
+    }
+
+    /// Digest an oracle signs for a transfer condition. Carries no nonce, so
+    /// an oracle's attestations don't race the manufacturer's signed
+    /// operations; each is accepted once, by `attestation_nullifier`.
+    pub fn attestation_message(&self, payload: &[u8]) -> Hash {
+        let mut hasher = Sha256::new();
+        update_field(&mut hasher, b"veri-charm/oracle-attestation");
+        update_field(&mut hasher, self.chain_id.as_bytes());
+        update_field(&mut hasher, self.contract.as_ref());
+        update_field(&mut hasher, self.manufacturer.as_ref());
+        update_field(&mut hasher, payload);
+        hasher.finalize().into()
+    }
+}
+
+/// Hash a length-prefixed field so adjacent fields cannot be re-split
+fn update_field(hasher: &mut Sha256, field: &[u8]) {
+    hasher.update((field.len() as u64).to_be_bytes());
+    hasher.update(field);
+}
+
+/// Encoding of `fields` with each one prefixed by its 8-byte big-endian
+/// length, so `("x", "yz")` and `("xy", "z")` never encode alike
+pub fn canonical_encode(fields: &[&[u8]]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(fields.iter().map(|field| field.len() + 8).sum());
+    for field in fields {
+        encoded.extend_from_slice(&(field.len() as u64).to_be_bytes());
+        encoded.extend_from_slice(field);
+    }
+    encoded
+}
+
+/// `sha256(canonical_encode(fields))`, for hash-derived identifiers
+pub fn canonical_hash(fields: &[&[u8]]) -> Hash {
+    Sha256::digest(canonical_encode(fields)).into()
+}
+
+/// Verify a signed operation under `domain` with the signer's `nonce`
+pub fn verify_in_domain(
+    domain: &SigningDomain,
+    nonce: u64,
+    payload: &[u8],
+    public_key: &PublicKey,
+    signature: &Signature,
+) -> bool {
+    verify_signature(public_key, &domain.message(nonce, payload), signature)
+}
+
+/// Payload an oracle signs, through `SigningDomain::attestation_message`,
+/// to attest that `condition` holds for moving `product_id` to `new_owner`
+/// through `valid_until`
+pub fn condition_payload(
+    product_id: &ProductId,
+    new_owner: &Address,
+    condition: &str,
+    valid_until: u64,
+) -> Vec<u8> {
+    canonical_encode(&[
+        b"veri-charm/transfer-condition",
+        product_id.as_bytes(),
+        new_owner.as_ref(),
+        condition.as_bytes(),
+        &valid_until.to_be_bytes(),
+    ])
+}
+
+/// Single-use tag of an oracle attestation for moving `product_id` to
+/// `new_owner`, spent when the transfer it gates completes
+pub fn attestation_nullifier(product_id: &ProductId, new_owner: &Address, valid_until: u64) -> Hash {
+    canonical_hash(&[
+        b"veri-charm/attestation-nullifier",
+        product_id.as_bytes(),
+        new_owner.as_ref(),
+        &valid_until.to_be_bytes(),
+    ])
+}
+
+/// Message an NFC tag signs when scanned, binding the token's current
+/// verification hash to the challenge block
+pub fn tag_message(product_id: &ProductId, verification_hash: &Hash, challenge_block: u64) -> Hash {
+    canonical_hash(&[
+        b"veri-charm/tag-scan",
+        product_id.as_bytes(),
+        verification_hash,
+        &challenge_block.to_be_bytes(),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{address, public_key, sign, signing_key};
+
+    fn domain(chain_id: &str) -> SigningDomain {
+        SigningDomain {
+            chain_id: chain_id.into(),
+            contract: address(0xcc),
+            manufacturer: address(1),
+        }
+    }
+
+    #[test]
+    fn canonical_encoding_keeps_field_boundaries() {
+        assert_ne!(canonical_encode(&[b"x", b"yz"]), canonical_encode(&[b"xy", b"z"]));
+        assert_ne!(canonical_hash(&[b"x", b"yz"]), canonical_hash(&[b"xy", b"z"]));
+        assert_ne!(canonical_hash(&[b"xyz"]), canonical_hash(&[b"xyz", b""]));
+        assert_eq!(canonical_encode(&[b"ab"]), [&2u64.to_be_bytes()[..], b"ab"].concat());
+    }
+
+    #[test]
+    fn signed_messages_are_bound_to_domain_and_nonce() {
+        let payload = b"payload";
+        let message = domain("bitcoin").message(0, payload);
+        assert_ne!(message, domain("bitcoin").message(1, payload));
+        assert_ne!(message, domain("cardano").message(0, payload));
+        assert_ne!(message, domain("bitcoin").product_message(payload));
+        assert_ne!(domain("bitcoin").product_message(payload), domain("cardano").product_message(payload));
+        let other_manufacturer = SigningDomain { manufacturer: address(2), ..domain("bitcoin") };
+        assert_ne!(message, other_manufacturer.message(0, payload));
+    }
+
+    #[test]
+    fn tag_messages_bind_the_challenge_block() {
+        let product_id: ProductId = "product-1".into();
+        let hash = [1u8; 32];
+        assert_ne!(tag_message(&product_id, &hash, 10), tag_message(&product_id, &hash, 11));
+        assert_ne!(tag_message(&product_id, &hash, 10), tag_message(&product_id, &[2u8; 32], 10));
+    }
+
+    #[test]
+    fn malformed_keys_and_signatures_are_rejected() {
+        assert!(!verify_signature(&vec![0u8; 5], b"message", &vec![0u8; 64]));
+        assert!(!verify_signature(&vec![0u8; 32], b"message", &vec![0u8; 10]));
+    }
+
+    #[test]
+    fn tag_signatures_verify_only_for_the_signed_scan() {
+        let tag = signing_key(7);
+        let product_id: ProductId = "product-1".into();
+        let message = tag_message(&product_id, &[1u8; 32], 10);
+        let signature = sign(&tag, &message);
+
+        assert!(verify_signature(&public_key(&tag), &message, &signature));
+        // Forged by another chip, or replayed against a later challenge
+        assert!(!verify_signature(&public_key(&tag), &message, &sign(&signing_key(8), &message)));
+        assert!(!verify_signature(&public_key(&tag), &tag_message(&product_id, &[1u8; 32], 11), &signature));
+    }
+
+    #[test]
+    fn in_domain_signatures_verify_only_in_their_domain_and_nonce() {
+        let key = signing_key(1);
+        let payload = b"payload";
+        let signature = sign(&key, &domain("bitcoin").message(3, payload));
+
+        assert!(verify_in_domain(&domain("bitcoin"), 3, payload, &public_key(&key), &signature));
+        assert!(!verify_in_domain(&domain("bitcoin"), 4, payload, &public_key(&key), &signature));
+        assert!(!verify_in_domain(&domain("cardano"), 3, payload, &public_key(&key), &signature));
+        let other_manufacturer = SigningDomain { manufacturer: address(2), ..domain("bitcoin") };
+        assert!(!verify_in_domain(&other_manufacturer, 3, payload, &public_key(&key), &signature));
+        let other_contract = SigningDomain { contract: address(0xdd), ..domain("bitcoin") };
+        assert!(!verify_in_domain(&other_contract, 3, payload, &public_key(&key), &signature));
+    }
+}
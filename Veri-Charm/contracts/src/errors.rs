@@ -0,0 +1,225 @@
+//! Error types returned by Veri-Charm contract entry points
+
+use super::*;
+use thiserror::Error;
+
+/// Contract errors
+#[derive(Error, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum VeriCharmError {
+    #[error("caller is not a registered manufacturer")]
+    UnauthorizedManufacturer,
+    #[error("manufacturer is not verified")]
+    ManufacturerNotVerified,
+    #[error("manufacturer not found")]
+    ManufacturerNotFound,
+    #[error("product not found")]
+    ProductNotFound,
+    #[error("caller is not the token owner")]
+    NotTokenOwner,
+    #[error("token has been burned")]
+    TokenBurned,
+    #[error("warranty period is still active")]
+    WarrantyActive,
+    #[error("invalid ZK proof")]
+    InvalidProof,
+    #[error("caller is not the contract admin")]
+    Unauthorized,
+    #[error("NFC tag signature does not match the registered tag key")]
+    InvalidTagSignature,
+    #[error("caller is not a registered auditor")]
+    NotAuditor,
+    #[error("audit record not found")]
+    AuditRecordNotFound,
+    #[error("beam retry limit reached for this product")]
+    BeamRetriesExhausted,
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("token must be re-verified before transfer")]
+    VerificationStale,
+    #[error("token does not track a quantity")]
+    NotDivisible,
+    #[error("requested amount exceeds the available quantity")]
+    InsufficientQuantity,
+    #[error("product has not reached its expiry block")]
+    NotExpired,
+    #[error("no trust registry root configured")]
+    RegistryNotConfigured,
+    #[error("registry inclusion proof is invalid")]
+    InvalidRegistryProof,
+    #[error("invalid address")]
+    InvalidAddress,
+    #[error("token is frozen pending review")]
+    TokenFrozen,
+    #[error("address has already reported this token")]
+    AlreadyReported,
+    #[error("address is already registered")]
+    AlreadyRegistered,
+    #[error("caller has not reported this token")]
+    NotReporter,
+    #[error("dispute has been escalated to the arbitrator")]
+    DisputeEscalated,
+    #[error("caller is not the arbitrator")]
+    NotArbitrator,
+    #[error("dispute not found")]
+    DisputeNotFound,
+    #[error("dispute has already been ruled on")]
+    DisputeAlreadyRuled,
+    #[error("generated product ID already exists")]
+    DuplicateProductId,
+    #[error("ID namespace must be 1-32 characters of A-Z, 0-9 or '-'")]
+    InvalidNamespace,
+    #[error("ID namespace is already claimed")]
+    NamespaceTaken,
+    #[error("manufacturer already has an ID namespace")]
+    NamespaceAlreadySet,
+    #[error("snapshot interval has not elapsed")]
+    SnapshotTooEarly,
+    #[error("recipient is not a registered participant")]
+    RecipientNotParticipant,
+    #[error("product failed authenticity checks")]
+    NotAuthentic,
+    #[error("provenance proof does not match the stored history root")]
+    ProvenanceProofMismatch,
+    #[error("attached value is below the mint fee")]
+    InsufficientFee,
+    #[error("event tag must be 1-64 bytes")]
+    InvalidEventTag,
+    #[error("token has reached the co-owner limit")]
+    TooManyCoOwners,
+    #[error("beam payload version is not supported")]
+    IncompatiblePayloadVersion,
+    #[error("beam payload could not be decoded")]
+    InvalidBeamPayload,
+    #[error("batch not found")]
+    BatchNotFound,
+    #[error("retailer not found")]
+    RetailerNotFound,
+    #[error("no pending transfer for this product")]
+    PendingTransferNotFound,
+    #[error("caller is not the pending transfer recipient")]
+    NotPendingRecipient,
+    #[error("beam target chain is the source chain")]
+    SameChainBeam,
+    #[error("beam not found")]
+    BeamNotFound,
+    #[error("unlock tx does not have enough confirmations")]
+    InsufficientConfirmations,
+    #[error("token is blacklisted")]
+    TokenBlacklisted,
+    #[error("manufacturer has not consented to blacklisting this token")]
+    BlacklistConsentRequired,
+    #[error("batch exceeds the maximum size")]
+    BatchTooLarge,
+    #[error("warranty start block is in the future")]
+    InvalidWarrantyStart,
+    #[error("warranty has already been started")]
+    WarrantyAlreadyStarted,
+    #[error("SKU prefix must be non-empty and must not contain '-'")]
+    InvalidSkuPrefix,
+    #[error("transfer condition not attested by a trusted oracle")]
+    ConditionNotMet,
+    #[error("contract already initialized")]
+    AlreadyInitialized,
+    #[error("manufacturer name is empty")]
+    EmptyManufacturerName,
+    #[error("manufacturer already exists")]
+    ManufacturerAlreadyExists,
+    #[error("recipient is not a registered retailer")]
+    UnregisteredRetailer,
+    #[error("warranty period overflow")]
+    WarrantyOverflow,
+    #[error("no raffle entries to draw from")]
+    NoRaffleEntries,
+    #[error("raffle entry already recorded")]
+    DuplicateRaffleEntry,
+    #[error("beam timed out")]
+    BeamTimedOut,
+    #[error("beam is not in a completable state")]
+    InvalidBeamState,
+    #[error("beam has not timed out yet")]
+    BeamNotExpired,
+    #[error("token is locked by an active beam")]
+    TokenLocked,
+    #[error("product has been recalled")]
+    ProductRecalled,
+    #[error("page size exceeds the maximum")]
+    PageTooLarge,
+    #[error("caller is not an authorized minting operator")]
+    NotAuthorizedOperator,
+    #[error("new admin is already the admin")]
+    AdminTransferToSelf,
+    #[error("withdrawal exceeds treasury balance")]
+    InsufficientTreasury,
+    #[error("product ID appears more than once in the batch")]
+    DuplicateBatchItem,
+    #[error("recipient must accept a first transfer from this manufacturer")]
+    RecipientConsentRequired,
+    #[error("token is inside a bundle")]
+    TokenBundled,
+    #[error("bundle still holds child tokens")]
+    BundleNotEmpty,
+    #[error("bundle has no child tokens")]
+    EmptyBundle,
+    #[error("product data is empty, oversized or out of range")]
+    InvalidProductData,
+    #[error("ZK proof bytes could not be decoded")]
+    MalformedProof,
+    #[error("verifying key could not be decoded")]
+    InvalidVerifyingKey,
+    #[error("ZK proof must carry a nullifier bound to its public inputs")]
+    MissingNullifier,
+    #[error("ZK proof has already been used")]
+    ProofAlreadyUsed,
+    #[error("warranty has expired")]
+    WarrantyExpired,
+    #[error("warranty claim not found")]
+    ClaimNotFound,
+    #[error("warranty claim has already been resolved")]
+    ClaimAlreadyResolved,
+    #[error("mint rate limit reached for this block")]
+    RateLimited,
+    #[error("contract is paused")]
+    ContractPaused,
+    #[error("token has not been burned")]
+    NotBurned,
+    #[error("no burn receipt recorded for this token")]
+    BurnReceiptNotFound,
+    #[error("route holder is not a registered manufacturer or retailer")]
+    InvalidRoute,
+    #[error("product may not be sold in the retailer's region")]
+    RegionNotAllowed,
+    #[error("mint would exceed the maximum supply")]
+    MintCapReached,
+    #[error("a transfer of this token is already pending")]
+    TransferAlreadyPending,
+    #[error("manufacturer has been revoked")]
+    ManufacturerRevoked,
+    #[error("signed product data has already been minted")]
+    ProductDataAlreadyMinted,
+    #[error("oracle attestation has already been used")]
+    AttestationAlreadyUsed,
+    #[error("burn reason has already been committed")]
+    BurnAlreadyCommitted,
+    #[error("burn commitment blind is not a field element")]
+    InvalidCommitment,
+}
+
+impl From<TransferBlock> for VeriCharmError {
+    fn from(block: TransferBlock) -> Self {
+        match block {
+            TransferBlock::ProductNotFound => VeriCharmError::ProductNotFound,
+            TransferBlock::NotOwner => VeriCharmError::NotTokenOwner,
+            TransferBlock::Burned => VeriCharmError::TokenBurned,
+            TransferBlock::Frozen => VeriCharmError::TokenFrozen,
+            TransferBlock::VerificationStale => VeriCharmError::VerificationStale,
+            TransferBlock::RecipientNotParticipant => VeriCharmError::RecipientNotParticipant,
+            TransferBlock::Blacklisted => VeriCharmError::TokenBlacklisted,
+            TransferBlock::UnregisteredRetailer => VeriCharmError::UnregisteredRetailer,
+            TransferBlock::Locked => VeriCharmError::TokenLocked,
+            TransferBlock::Recalled => VeriCharmError::ProductRecalled,
+            TransferBlock::Bundled => VeriCharmError::TokenBundled,
+            TransferBlock::RegionNotAllowed => VeriCharmError::RegionNotAllowed,
+            TransferBlock::Paused => VeriCharmError::ContractPaused,
+        }
+    }
+}
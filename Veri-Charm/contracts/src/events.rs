@@ -0,0 +1,169 @@
+//! Structured contract events for off-chain indexers
+
+use super::*;
+
+/// Typed contract event
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum VeriCharmEvent {
+    Minted {
+        product_id: ProductId,
+        manufacturer: Address,
+        owner: Address,
+        event_tag: Option<String>,
+    },
+    Transferred {
+        product_id: ProductId,
+        from: Address,
+        to: Address,
+        event_tag: Option<String>,
+    },
+    Burned {
+        product_id: ProductId,
+        burner: Address,
+        reason: BurnReason,
+        event_tag: Option<String>,
+    },
+    BeamInitiated {
+        beam_id: BeamId,
+        product_id: ProductId,
+        target_chain: ChainId,
+    },
+    TokenAutoFrozen {
+        product_id: ProductId,
+        report_count: u32,
+    },
+    SnapshotPublished {
+        root: Hash,
+        block_height: u64,
+    },
+    BatchRecalled {
+        batch_id: Hash,
+        reason: String,
+        recalled: u32,
+    },
+    TransferOffered {
+        product_id: ProductId,
+        from: Address,
+        to: Address,
+    },
+    Split {
+        parent_id: ProductId,
+        child_id: ProductId,
+        to: Address,
+        amount: u64,
+    },
+    Reissued {
+        original_id: ProductId,
+        replacement_id: ProductId,
+    },
+    BeamCompleted {
+        beam_id: BeamId,
+        product_id: ProductId,
+    },
+    BeamCancelled {
+        beam_id: BeamId,
+        product_id: ProductId,
+    },
+    BeamSettled {
+        product_id: ProductId,
+        source_chain: ChainId,
+    },
+    TokenBlacklisted {
+        product_id: ProductId,
+    },
+    Recalled {
+        product_id: ProductId,
+        reason: String,
+    },
+    ManufacturerRevoked {
+        manufacturer: Address,
+    },
+    DisputeEscalated {
+        product_id: ProductId,
+        reporter: Address,
+    },
+    DisputeRuled {
+        product_id: ProductId,
+        ruling: Ruling,
+    },
+}
+
+impl VeriCharmEvent {
+    /// Whether the event must be emitted under `EventVerbosity::CriticalOnly`.
+    /// Routine lifecycle events are not critical.
+    pub fn is_critical(&self) -> bool {
+        match self {
+            VeriCharmEvent::Minted { .. }
+            | VeriCharmEvent::Transferred { .. }
+            | VeriCharmEvent::Burned { .. }
+            | VeriCharmEvent::BeamInitiated { .. }
+            | VeriCharmEvent::TransferOffered { .. }
+            | VeriCharmEvent::Split { .. }
+            | VeriCharmEvent::Reissued { .. }
+            | VeriCharmEvent::BeamCompleted { .. }
+            | VeriCharmEvent::BeamCancelled { .. }
+            | VeriCharmEvent::BeamSettled { .. } => false,
+            VeriCharmEvent::TokenAutoFrozen { .. }
+            | VeriCharmEvent::SnapshotPublished { .. }
+            | VeriCharmEvent::BatchRecalled { .. }
+            | VeriCharmEvent::TokenBlacklisted { .. }
+            | VeriCharmEvent::Recalled { .. }
+            | VeriCharmEvent::ManufacturerRevoked { .. }
+            | VeriCharmEvent::DisputeEscalated { .. }
+            | VeriCharmEvent::DisputeRuled { .. } => true,
+        }
+    }
+}
+
+/// Which events the contract emits
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EventVerbosity {
+    /// Emit every event
+    #[default]
+    All,
+    /// Emit only critical events (recalls, blacklists, disputes)
+    CriticalOnly,
+    /// Emit nothing
+    None,
+}
+
+impl EventVerbosity {
+    /// Whether `event` should be emitted at this verbosity
+    pub fn allows(&self, event: &VeriCharmEvent) -> bool {
+        match self {
+            EventVerbosity::All => true,
+            EventVerbosity::CriticalOnly => event.is_critical(),
+            EventVerbosity::None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::address;
+
+    #[test]
+    fn critical_only_drops_routine_events() {
+        let transfer = VeriCharmEvent::Transferred {
+            product_id: "VC-1".into(),
+            from: address(1),
+            to: address(2),
+            event_tag: None,
+        };
+        let recall = VeriCharmEvent::Recalled {
+            product_id: "VC-1".into(),
+            reason: "contamination".into(),
+        };
+        let dispute = VeriCharmEvent::DisputeRuled {
+            product_id: "VC-1".into(),
+            ruling: Ruling::Counterfeit,
+        };
+
+        assert!(!EventVerbosity::CriticalOnly.allows(&transfer));
+        assert!(EventVerbosity::CriticalOnly.allows(&recall));
+        assert!(EventVerbosity::CriticalOnly.allows(&dispute));
+        assert!(EventVerbosity::All.allows(&transfer));
+        assert!(!EventVerbosity::None.allows(&recall));
+    }
+}
@@ -0,0 +1,46 @@
+//! Mint fee schedule
+
+use super::*;
+
+/// Volume discount tier: manufacturers with at least `min_products_minted`
+/// cumulative mints pay `fee` per mint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeeTier {
+    pub min_products_minted: u64,
+    pub fee: u64,
+}
+
+/// Fee for the next mint by a manufacturer with `products_minted` prior mints.
+///
+/// Picks the tier with the highest threshold the manufacturer has reached;
+/// falls back to `base_fee` below every threshold. Tier order does not matter.
+pub fn effective_mint_fee(base_fee: u64, tiers: &[FeeTier], products_minted: u64) -> u64 {
+    tiers
+        .iter()
+        .filter(|tier| products_minted >= tier.min_products_minted)
+        .max_by_key(|tier| tier.min_products_minted)
+        .map_or(base_fee, |tier| tier.fee)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tier(min_products_minted: u64, fee: u64) -> FeeTier {
+        FeeTier { min_products_minted, fee }
+    }
+
+    #[test]
+    fn base_fee_applies_below_every_tier() {
+        assert_eq!(effective_mint_fee(100, &[], 1_000), 100);
+        assert_eq!(effective_mint_fee(100, &[tier(10, 80)], 9), 100);
+    }
+
+    #[test]
+    fn highest_reached_tier_applies_in_any_order() {
+        let tiers = [tier(1_000, 50), tier(10, 80), tier(100, 60)];
+        assert_eq!(effective_mint_fee(100, &tiers, 10), 80);
+        assert_eq!(effective_mint_fee(100, &tiers, 999), 60);
+        assert_eq!(effective_mint_fee(100, &tiers, 1_000), 50);
+    }
+}
@@ -1,131 +1,786 @@
 //! Veri-Charm Protocol: Anti-counterfeiting solution using Charms SDK
 //! Main contract handling Charm token lifecycle
 
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
 extern crate alloc;
-use alloc::{vec::Vec, string::String, format};
+use alloc::{vec, vec::Vec, string::String, format};
+use alloc::collections::{BTreeMap, BTreeSet};
 use charms_sdk::prelude::*;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 mod types;
 mod charm;
+mod burn_reason;
+mod crypto;
+mod events;
+mod fees;
+mod merkle;
+mod pagination;
+mod raffle;
+mod serial;
+mod storage;
 mod verification;
 mod errors;
+#[cfg(test)]
+mod test_utils;
 
 use types::*;
-use charm::CharmToken;
-use verification::VerificationCircuit;
+use charm::*;
+use verification::{ConfidenceSignals, Field, VerificationCircuit, ZkProof};
 use errors::VeriCharmError;
+use crypto::{PublicKey, Signature, SigningDomain};
+use events::{EventVerbosity, VeriCharmEvent};
+use fees::{effective_mint_fee, FeeTier};
+use merkle::{MerkleAccumulator, MerkleProof, NodeKey};
+use pagination::paginate_process;
+use serial::{normalize_serial, serial_code};
+use storage::{HostStorage, Storage, StorageBackend};
 
 /// Main contract state
 #[derive(Serialize, Deserialize, Default)]
-pub struct VeriCharmContract {
+#[serde(bound = "")]
+pub struct VeriCharmContract<S: StorageBackend = HostStorage> {
     /// Mapping of product IDs to their Charm tokens
-    pub products: Map<ProductId, CharmToken>,
+    pub products: S::Map<ProductId, CharmToken>,
     /// Manufacturer registry
-    pub manufacturers: Map<Address, Manufacturer>,
+    pub manufacturers: S::Map<Address, Manufacturer>,
     /// Retailer registry  
-    pub retailers: Map<Address, Retailer>,
+    pub retailers: S::Map<Address, Retailer>,
     /// Cross-chain beam records
-    pub beam_records: Map<BeamId, CrossChainBeam>,
+    pub beam_records: S::Map<BeamId, CrossChainBeam>,
     /// Verification circuit parameters
     pub circuit_params: VerificationCircuit,
     /// Total tokens minted counter
     pub total_minted: u64,
+    /// Contract administrator, set once in `init`
+    #[serde(default)]
+    pub admin: Option<Address>,
+    /// In-progress manufacturer stats recomputations: (`mint_sequence` resume
+    /// cursor, partial count)
+    #[serde(default)]
+    pub stats_recompute: S::Map<Address, (u64, u64)>,
+    /// Merkle accumulator of minted products' verification hashes
+    #[serde(default)]
+    pub mint_accumulator: MerkleAccumulator,
+    /// Raw leaves and completed nodes of `mint_accumulator`
+    #[serde(default)]
+    pub mint_tree: S::Map<NodeKey, Hash>,
+    /// Leaf index of each product in `mint_accumulator`
+    #[serde(default)]
+    pub mint_leaf_index: S::Map<ProductId, u64>,
+    /// Addresses allowed to perform logged regulatory verifications
+    #[serde(default)]
+    pub auditors: S::Map<Address, bool>,
+    /// Append-only regulatory audit log
+    #[serde(default)]
+    pub audit_log: S::Map<AuditId, AuditRecord>,
+    /// Number of audit records written
+    #[serde(default)]
+    pub audit_count: u64,
+    /// Hash of the latest audit record, chaining the log
+    #[serde(default)]
+    pub audit_head: Hash,
+    /// Latest beam initiated for each product
+    #[serde(default)]
+    pub latest_beam: S::Map<ProductId, BeamId>,
+    /// Maximum beam attempts per product (0 = `DEFAULT_MAX_BEAM_ATTEMPTS`)
+    #[serde(default)]
+    pub max_beam_attempts: u32,
+    /// Next expected nonce per signer for domain-bound signed operations
+    #[serde(default)]
+    pub signer_nonces: S::Map<Address, u64>,
+    /// Signed product-data digests already minted, so each signature mints once
+    #[serde(default)]
+    pub used_product_digests: S::Map<Hash, bool>,
+    /// Root of the external trust-anchor registry of legitimate manufacturers
+    #[serde(default)]
+    pub trust_registry_root: Option<Hash>,
+    /// Which events are emitted
+    #[serde(default)]
+    pub event_verbosity: EventVerbosity,
+    /// Distinct addresses that reported each token as counterfeit
+    #[serde(default)]
+    pub counterfeit_reports: S::Map<ProductId, Vec<Address>>,
+    /// Distinct reports that auto-freeze a token (0 disables auto-freeze)
+    #[serde(default)]
+    pub auto_freeze_report_threshold: u32,
+    /// Admin-appointed arbitrator for escalated disputes
+    #[serde(default)]
+    pub arbitrator: Option<Address>,
+    /// Escalated counterfeit disputes
+    #[serde(default)]
+    pub disputes: S::Map<ProductId, Dispute>,
+    /// Disputes ruled `Authentic`, archived so the token can be disputed again
+    #[serde(default)]
+    pub archived_disputes: S::Map<ProductId, Vec<Dispute>>,
+    /// Claimed product ID namespaces and their owners
+    #[serde(default)]
+    pub id_namespaces: S::Map<String, Address>,
+    /// Blocks between published state snapshots (0 disables publishing)
+    #[serde(default)]
+    pub snapshot_interval: u64,
+    /// Block the last snapshot scan started at
+    #[serde(default)]
+    pub last_snapshot_block: u64,
+    /// Snapshot scan in progress, resumed by `publish_snapshot`
+    #[serde(default)]
+    pub pending_snapshot: Option<SnapshotScan>,
+    /// Most recently completed snapshot, served by `snapshot_proof`
+    #[serde(default)]
+    pub published_snapshot: Option<StateSnapshot>,
+    /// Tree nodes of the published and the in-progress snapshot; each new
+    /// snapshot is built in the buffer the published one isn't using
+    #[serde(default)]
+    pub snapshot_trees: [S::Map<NodeKey, Hash>; 2],
+    /// Leaf index of each product in the snapshot tree of the same buffer
+    #[serde(default)]
+    pub snapshot_leaf_index: [S::Map<ProductId, u64>; 2],
+    /// Restrict transfers to registered manufacturers and retailers
+    #[serde(default)]
+    pub closed_ecosystem: bool,
+    /// Running warranty liability per manufacturer
+    #[serde(default)]
+    pub warranty_liabilities: S::Map<Address, Liability>,
+    /// Base fee charged per mint
+    #[serde(default)]
+    pub mint_fee: u64,
+    /// Volume discount tiers applied to `mint_fee`
+    #[serde(default)]
+    pub fee_tiers: Vec<FeeTier>,
+    /// Maximum co-owners per token (0 = `DEFAULT_MAX_CO_OWNERS`)
+    #[serde(default)]
+    pub max_co_owners: u32,
+    /// Token ids in each production batch, in mint order
+    #[serde(default)]
+    pub batch_members: S::Map<Hash, Vec<ProductId>>,
+    /// Seconds per block used to convert warranty periods; 0 uses the default
+    #[serde(default)]
+    pub block_time_secs: u64,
+    /// Route transfers to first-time holders of a manufacturer's tokens
+    /// through the offer/accept flow
+    #[serde(default)]
+    pub first_contact_consent: bool,
+    /// Tokens each (holder, manufacturer) pair has received
+    #[serde(default)]
+    pub holder_receipts: S::Map<(Address, Address), u64>,
+    /// Transfer offers awaiting acceptance
+    #[serde(default)]
+    pub pending_transfers: S::Map<ProductId, PendingTransfer>,
+    /// Manufacturer-supplied circuits whose proofs attest results for their brand
+    #[serde(default)]
+    pub manufacturer_circuits: S::Map<Address, VerificationCircuit>,
+    /// Beam parameters per target chain
+    #[serde(default)]
+    pub chain_info: S::Map<ChainId, ChainInfo>,
+    /// Who may call `blacklist_token`
+    #[serde(default)]
+    pub blacklist_policy: BlacklistPolicy,
+    /// Tokens whose manufacturer has consented to blacklisting
+    #[serde(default)]
+    pub blacklist_consents: S::Map<ProductId, bool>,
+    /// Items allowed per `batch_mint_charms` call; 0 uses the default
+    #[serde(default)]
+    pub max_batch_mint: u32,
+    /// Tokens burned by any path
+    #[serde(default)]
+    pub total_burned: u64,
+    /// Tokens created without a mint: split children and settled incoming beams
+    #[serde(default)]
+    pub total_derived: u64,
+    /// Last counter issued per (manufacturer, SKU prefix)
+    #[serde(default)]
+    pub sku_counters: S::Map<(Address, String), u64>,
+    /// Last counter issued per claimed ID namespace. Kept apart from
+    /// `products_minted`, which `recompute_manufacturer_stats` may lower.
+    #[serde(default)]
+    pub namespace_counters: S::Map<String, u64>,
+    /// Oracle keys each manufacturer trusts for conditional transfers
+    #[serde(default)]
+    pub oracle_keys: S::Map<Address, Vec<PublicKey>>,
+    /// Safe mode: ignore ZK proofs and verify on signatures and provenance only
+    #[serde(default)]
+    pub zk_disabled: bool,
+    /// Fee per block of purchased warranty extension
+    #[serde(default)]
+    pub warranty_extension_rate: u64,
+    /// Raffle entries from burns awaiting a draw, keyed by `entry_id`
+    #[serde(default)]
+    pub raffle_entries: S::Map<Hash, RaffleEntry>,
+    /// Number of entries in `raffle_entries`
+    #[serde(default)]
+    pub open_raffle_entries: u64,
+    /// Live token IDs held by each owner
+    #[serde(default)]
+    pub owner_index: S::Map<Address, Vec<ProductId>>,
+    /// Minted product IDs by mint order (accumulator leaf index)
+    #[serde(default)]
+    pub mint_sequence: S::Map<u64, ProductId>,
+    /// Minting operator -> manufacturer it mints for
+    #[serde(default)]
+    pub operators: S::Map<Address, Address>,
+    /// Nominated admin awaiting `accept_admin`
+    #[serde(default)]
+    pub pending_admin: Option<Address>,
+    /// Fees collected by the protocol and not yet withdrawn
+    #[serde(default)]
+    pub treasury: u64,
+    /// Salt for serial numbers, fixed at init so serials can't be derived
+    /// from the mint order alone
+    #[serde(default)]
+    pub serial_salt: Hash,
+    /// Nullifiers of ZK proofs already accepted by `verify_product_mut`
+    #[serde(default)]
+    pub used_nullifiers: S::Map<Hash, bool>,
+    /// Warranty claims by ID
+    #[serde(default)]
+    pub claims: S::Map<ClaimId, WarrantyClaim>,
+    /// ID assigned to the next warranty claim
+    #[serde(default)]
+    pub next_claim_id: ClaimId,
+    /// Number of registered manufacturers
+    #[serde(default)]
+    pub manufacturer_count: u64,
+    /// Mints allowed per manufacturer per block; 0 = unlimited
+    #[serde(default)]
+    pub mint_rate_limit: u32,
+    /// Per-manufacturer `(block, mints in that block)`
+    #[serde(default)]
+    pub mint_windows: S::Map<Address, (u64, u32)>,
+    /// Emergency stop: mutating entry points fail while set
+    #[serde(default)]
+    pub paused: bool,
+    /// Receipts of burned tokens, kept for warranty-return auditing
+    #[serde(default)]
+    pub burn_receipts: S::Map<ProductId, BurnReceipt>,
+    /// Cap on `total_minted`, if any
+    #[serde(default)]
+    pub max_supply: Option<u64>,
+    /// Nullifiers of oracle attestations spent by completed conditional transfers
+    #[serde(default)]
+    pub used_attestations: S::Map<Hash, bool>,
+    /// Groth16 verifying key of the burn-reason circuit
+    #[serde(default)]
+    pub burn_reason_circuit: VerificationCircuit,
+    /// Burn commitments recorded by `commit_burn_reason`
+    #[serde(default)]
+    pub burn_commitments: S::Map<Field, bool>,
+    /// Burned tokens whose reason has been committed
+    #[serde(default)]
+    pub committed_burns: S::Map<ProductId, bool>,
 }
 
-/// Contract implementation
-impl Contract for VeriCharmContract {
-    type Error = VeriCharmError;
+/// Maximum tokens scanned per `recompute_manufacturer_stats` call
+pub const MAX_RECOMPUTE_BATCH: usize = 500;
 
-    /// Initialize contract with manufacturer
-    fn init(&mut self, ctx: &Context) -> Result<(), Self::Error> {
-        // Ensure only authorized manufacturers can initialize
-        let manufacturer = Manufacturer {
-            address: ctx.sender.clone(),
-            name: String::from("Initial Manufacturer"),
-            verified: true,
-            products_minted: 0,
-        };
+/// Items per `batch_mint_charms` call when no limit is configured
+pub const DEFAULT_MAX_BATCH_MINT: u32 = 1000;
+
+/// Maximum bytes of a product name written to logs
+pub const MAX_LOG_NAME_LEN: usize = 64;
+
+/// Maximum bytes of a `ProductData` string field
+pub const MAX_PRODUCT_FIELD_LEN: usize = 256;
+
+/// Maximum length of a manufacturer's product ID namespace
+pub const MAX_NAMESPACE_LEN: usize = 32;
+
+/// Maximum length of a manufacturer's event tag
+pub const MAX_EVENT_TAG_LEN: usize = 64;
+
+/// Co-owner limit per token when none is configured
+pub const DEFAULT_MAX_CO_OWNERS: u32 = 4;
+
+/// Default warranty period granted at mint
+pub const WARRANTY_PERIOD_SECS: u64 = 1_209_600; // 14 days
+
+/// Assumed seconds per block when none is configured
+pub const DEFAULT_BLOCK_TIME_SECS: u64 = 600;
+
+/// Blocks after mint within which a sale may start the warranty (~6 months);
+/// a later `start_warranty` counts coverage from the end of this window
+pub const WARRANTY_ACTIVATION_BLOCKS: u64 = 26_280;
+
+/// Current `CharmToken::warranty_schema`: `warranty_period` counts blocks
+pub const WARRANTY_SCHEMA_VERSION: u16 = 1;
+
+/// Maximum tokens scanned per `migrate` call
+pub const MAX_MIGRATE_BATCH: usize = 500;
+
+/// Maximum records visited per `check_invariants` call
+pub const MAX_INVARIANT_BATCH: usize = 500;
+
+/// Maximum tokens appended per `publish_snapshot` call
+pub const MAX_SNAPSHOT_BATCH: usize = 500;
+
+/// Maximum page size for `list_products`
+pub const MAX_PRODUCT_PAGE: u32 = 100;
+
+/// Maximum tokens moved per `batch_transfer` call
+pub const MAX_BATCH_TRANSFER: usize = 500;
+
+/// Maximum tokens flagged per `recall_batch` call
+pub const MAX_RECALL_BATCH: usize = 500;
+
+/// Maximum reissue links followed when reporting recall lineage
+pub const MAX_LINEAGE_DEPTH: usize = 16;
+
+/// Blocks a tag scan challenge stays valid for `verify_product`
+pub const TAG_SCAN_WINDOW_BLOCKS: u64 = 6;
+
+/// Blocks a beam has to complete before it expires
+pub const BEAM_TIMEOUT_BLOCKS: u64 = 100;
+
+/// Serialization version of beamed token payloads produced by this contract
+pub const BEAM_PAYLOAD_VERSION: u16 = 1;
+
+/// Beam attempts allowed per product when no limit is configured
+pub const DEFAULT_MAX_BEAM_ATTEMPTS: u32 = 3;
+
+impl<S: StorageBackend> VeriCharmContract<S> {
+    /// Fail while the contract is paused
+    fn ensure_not_paused(&self) -> Result<(), VeriCharmError> {
+        if self.paused {
+            return Err(VeriCharmError::ContractPaused);
+        }
+        Ok(())
+    }
+
+    /// Ensure the caller is the contract admin
+    fn ensure_admin(&self, ctx: &Context) -> Result<(), VeriCharmError> {
+        match &self.admin {
+            Some(admin) if *admin == ctx.sender => Ok(()),
+            _ => Err(VeriCharmError::Unauthorized),
+        }
+    }
+
+    /// Check every rule that would block `transfer_charm`
+    fn check_transfer(
+        &self,
+        ctx: &Context,
+        charm_token: &CharmToken,
+        new_owner: &Address,
+    ) -> Result<(), TransferBlock> {
+        self.check_transfer_by(ctx, &ctx.sender, charm_token, new_owner)
+    }
+
+    /// Check every rule that would block `sender` moving the token to `new_owner`
+    fn check_transfer_by(
+        &self,
+        ctx: &Context,
+        sender: &Address,
+        charm_token: &CharmToken,
+        new_owner: &Address,
+    ) -> Result<(), TransferBlock> {
+        // Verify current owner is the sender
+        if !charm_token.is_owned_by(sender) {
+            return Err(TransferBlock::NotOwner);
+        }
         
-        self.manufacturers.insert(ctx.sender.clone(), manufacturer);
+        // Check if token is burned
+        if charm_token.burned {
+            return Err(TransferBlock::Burned);
+        }
         
-        // Initialize ZK circuit parameters
-        self.circuit_params = VerificationCircuit::default();
+        if charm_token.frozen {
+            return Err(TransferBlock::Frozen);
+        }
+        
+        if charm_token.blacklisted {
+            return Err(TransferBlock::Blacklisted);
+        }
+        
+        if charm_token.locked_beam.is_some() {
+            return Err(TransferBlock::Locked);
+        }
+        
+        if charm_token.recalled {
+            return Err(TransferBlock::Recalled);
+        }
+        
+        // Bundled tokens move with their parent
+        if charm_token.parent.is_some() {
+            return Err(TransferBlock::Bundled);
+        }
+        
+        // Closed B2B deployments only move tokens between registered participants
+        if self.closed_ecosystem
+            && !self.manufacturers.contains_key(new_owner)
+            && !self.retailers.contains_key(new_owner)
+        {
+            return Err(TransferBlock::RecipientNotParticipant);
+        }
+        
+        // Brands can restrict a token to their authorized distribution network
+        if charm_token.metadata.require_registered_retailer
+            && !self.retailers.contains_key(new_owner)
+        {
+            return Err(TransferBlock::UnregisteredRetailer);
+        }
+        
+        // Region restrictions apply to retailers; consumers can receive anywhere
+        let allowed_regions = &charm_token.product_data.allowed_regions;
+        if let Some(retailer) = self.retailers.get(new_owner) {
+            if !allowed_regions.is_empty() && !allowed_regions.contains(&retailer.region) {
+                return Err(TransferBlock::RegionNotAllowed);
+            }
+        }
+        
+        // Enforce the manufacturer's verification freshness policy
+        let freshness = self.manufacturers.get(&charm_token.manufacturer)
+            .and_then(|manufacturer| manufacturer.require_recent_verification);
+        if let Some(max_age) = freshness {
+            let fresh = charm_token.last_verified_block
+                .is_some_and(|verified_at| ctx.block_height.saturating_sub(verified_at) <= max_age);
+            if !fresh {
+                return Err(TransferBlock::VerificationStale);
+            }
+        }
         
-        log!("Veri-Charm contract initialized by {}", ctx.sender);
         Ok(())
     }
 
-    /// Mint a new Charm token for a physical product
-    #[payable]
-    fn mint_charm(
-        &mut self, 
+    /// Clear secondary state that references a burned token so nothing is left
+    /// pointing at it. Any new structure keyed by `ProductId` (approvals,
+    /// listings, escrows, scheduled transfers) must be cleared here.
+    fn cleanup_token_associations(&mut self, product_id: &ProductId) {
+        if let Some(charm_token) = self.products.get(product_id) {
+            self.unindex_owner(&charm_token.current_owner, product_id);
+        }
+        self.pending_transfers.remove(product_id);
+        self.blacklist_consents.remove(product_id);
+    }
+
+    /// Verify a signed operation in the domain of `manufacturer` and consume the
+    /// signer's nonce. Every nonce-ordered signature goes through here; product
+    /// data is signed nonce-free (see `verify_product_signature`).
+    fn verify_signed_operation(
+        &mut self,
         ctx: &Context,
-        product_data: ProductData,
-        metadata: TokenMetadata,
-    ) -> Result<CharmToken, Self::Error> {
-        // Verify caller is registered manufacturer
-        let manufacturer = self.manufacturers.get(&ctx.sender)
-            .ok_or(VeriCharmError::UnauthorizedManufacturer)?;
+        signer: &Address,
+        manufacturer: &Address,
+        public_key: &PublicKey,
+        payload: &[u8],
+        signature: &Signature,
+    ) -> Result<(), VeriCharmError> {
+        let domain = SigningDomain {
+            chain_id: ctx.chain_id.clone(),
+            contract: ctx.contract_address.clone(),
+            manufacturer: manufacturer.clone(),
+        };
+        let nonce = self.signer_nonces.get(signer).unwrap_or(0);
         
-        if !manufacturer.verified {
-            return Err(VeriCharmError::ManufacturerNotVerified);
+        if !crypto::verify_in_domain(&domain, nonce, payload, public_key, signature) {
+            return Err(VeriCharmError::InvalidSignature);
         }
-
-        // Generate unique product ID
-        self.total_minted += 1;
-        let product_id = format!("{}-{:06}", manufacturer.address, self.total_minted);
         
-        // Create Charm token
-        let charm_token = CharmToken {
-            id: product_id.clone(),
-            manufacturer: ctx.sender.clone(),
-            product_data,
-            metadata,
-            current_owner: ctx.sender.clone(),
-            mint_time: ctx.block_height,
-            warranty_period: 1209600, // 14 days in seconds
-            burned: false,
-            transfer_history: Vec::new(),
-            zk_proof: None,
+        self.signer_nonces.insert(signer.clone(), nonce + 1);
+        Ok(())
+    }
+
+    /// Check the manufacturer's signature over product data being minted,
+    /// without recording anything. Returns the signed digest, which the caller
+    /// marks used once the mint can no longer fail.
+    fn verify_product_signature(
+        &self,
+        ctx: &Context,
+        manufacturer: &Manufacturer,
+        product_data: &ProductData,
+    ) -> Result<Hash, VeriCharmError> {
+        let public_key = manufacturer.public_key.as_ref()
+            .ok_or(VeriCharmError::InvalidSignature)?;
+        let domain = SigningDomain {
+            chain_id: ctx.chain_id.clone(),
+            contract: ctx.contract_address.clone(),
+            manufacturer: manufacturer.address.clone(),
         };
+        let digest = domain.product_message(&product_data.signing_payload());
+        if !crypto::verify_signature(public_key, &digest, &product_data.product_signature) {
+            return Err(VeriCharmError::InvalidSignature);
+        }
+        if self.used_product_digests.contains_key(&digest) {
+            return Err(VeriCharmError::ProductDataAlreadyMinted);
+        }
+        Ok(digest)
+    }
+
+    /// Emit a structured event as JSON, subject to `event_verbosity`
+    fn emit_event(&self, event: VeriCharmEvent) {
+        if !self.event_verbosity.allows(&event) {
+            return;
+        }
+        if let Ok(payload) = serde_json::to_string(&event) {
+            log!("event:{}", payload);
+        }
+    }
+
+    /// Reject the zero address and the contract's own address
+    fn validate_address(&self, ctx: &Context, address: &Address) -> Result<(), VeriCharmError> {
+        if *address == Address::default() || *address == ctx.contract_address {
+            return Err(VeriCharmError::InvalidAddress);
+        }
+        Ok(())
+    }
 
-        // Store token
-        self.products.insert(product_id, charm_token.clone());
+    /// Insert a new unverified manufacturer after address checks
+    fn insert_manufacturer(
+        &mut self,
+        ctx: &Context,
+        address: Address,
+        name: String,
+        public_key: PublicKey,
+    ) -> Result<(), VeriCharmError> {
+        self.validate_address(ctx, &address)?;
         
-        // Update manufacturer stats
-        let mut updated_manufacturer = manufacturer.clone();
-        updated_manufacturer.products_minted += 1;
-        self.manufacturers.insert(ctx.sender.clone(), updated_manufacturer);
+        if name.trim().is_empty() {
+            return Err(VeriCharmError::EmptyManufacturerName);
+        }
+        if self.manufacturers.contains_key(&address) {
+            return Err(VeriCharmError::ManufacturerAlreadyExists);
+        }
         
-        log!("Charm token minted: {} for product: {}", 
-             product_id, charm_token.product_data.name);
+        let mut manufacturer = Manufacturer::new(address.clone(), name, false);
+        manufacturer.public_key = Some(public_key);
+        self.manufacturers.insert(address.clone(), manufacturer);
+        self.manufacturer_count += 1;
         
-        Ok(charm_token)
+        log!("Manufacturer registered: {}", address);
+        Ok(())
     }
 
-    /// Transfer Charm token to new owner (retailer or consumer)
-    fn transfer_charm(
+    /// Remove a token's declared value from its manufacturer's warranty liability
+    fn release_liability(&mut self, charm_token: &mut CharmToken) {
+        if charm_token.liability_released {
+            return;
+        }
+        charm_token.liability_released = true;
+        
+        let mut liability = self.warranty_liabilities.get(&charm_token.manufacturer)
+            .unwrap_or_default();
+        liability.token_count = liability.token_count.saturating_sub(1);
+        liability.total_value = liability.total_value
+            .saturating_sub(charm_token.product_data.declared_value);
+        self.warranty_liabilities.insert(charm_token.manufacturer.clone(), liability);
+    }
+
+    /// Count a released token toward its manufacturer's warranty liability
+    /// again once its coverage is renewed. Split tokens stay released: their
+    /// parent's declared value covers them.
+    fn restore_liability(&mut self, charm_token: &mut CharmToken) {
+        if !charm_token.liability_released || charm_token.burned || charm_token.split_from.is_some() {
+            return;
+        }
+        charm_token.liability_released = false;
+        
+        let mut liability = self.warranty_liabilities.get(&charm_token.manufacturer)
+            .unwrap_or_default();
+        liability.token_count += 1;
+        liability.total_value = liability.total_value
+            .saturating_add(charm_token.product_data.declared_value);
+        self.warranty_liabilities.insert(charm_token.manufacturer.clone(), liability);
+    }
+
+    /// Event tag configured by a token's manufacturer
+    fn event_tag_for(&self, manufacturer: &Address) -> Option<String> {
+        self.manufacturers.get(manufacturer)
+            .and_then(|manufacturer| manufacturer.event_tag)
+    }
+
+    /// Allocate the next product ID for `manufacturer`
+    fn next_product_id(
         &mut self,
-        ctx: &Context,
-        product_id: ProductId,
-        new_owner: Address,
-        zk_proof: Option<ZkProof>,
-    ) -> Result<(), Self::Error> {
-        let mut charm_token = self.products.get(&product_id)
-            .ok_or(VeriCharmError::ProductNotFound)?;
+        manufacturer: &Manufacturer,
+        product_data: &ProductData,
+    ) -> Result<ProductId, VeriCharmError> {
+        let mut product_ids = self.allocate_product_ids(manufacturer, [product_data])?;
+        Ok(product_ids.remove(0))
+    }
+
+    /// Allocate contiguous product IDs for `manufacturer`, one per product.
+    /// Products with a SKU prefix draw from that prefix's own counter. Nothing
+    /// is reserved unless every ID is free.
+    fn allocate_product_ids<'a>(
+        &mut self,
+        manufacturer: &Manufacturer,
+        products: impl IntoIterator<Item = &'a ProductData>,
+    ) -> Result<Vec<ProductId>, VeriCharmError> {
+        let mut sku_counters: BTreeMap<String, u64> = BTreeMap::new();
+        // Namespaces claimed before `namespace_counters` existed continue
+        // from the mint count
+        let mut namespace_counter = manufacturer.id_namespace.as_ref().map(|namespace| {
+            self.namespace_counters.get(namespace).unwrap_or(manufacturer.products_minted)
+        });
+        let mut product_ids = Vec::new();
+        for (offset, product_data) in (1u64..).zip(products) {
+            let minted = self.total_minted.checked_add(offset)
+                .filter(|minted| self.max_supply.map_or(true, |cap| *minted <= cap))
+                .ok_or(VeriCharmError::MintCapReached)?;
+            let product_id = match &product_data.sku_prefix {
+                Some(prefix) => {
+                    if prefix.is_empty() || prefix.contains('-') {
+                        return Err(VeriCharmError::InvalidSkuPrefix);
+                    }
+                    let counter = match sku_counters.get(prefix) {
+                        Some(counter) => *counter,
+                        None => self.sku_counters
+                            .get(&(manufacturer.address.clone(), prefix.clone()))
+                            .unwrap_or(0),
+                    } + 1;
+                    sku_counters.insert(prefix.clone(), counter);
+                    format!("{}-{}-{:06}", prefix, manufacturer.address, counter)
+                }
+                None => match (&manufacturer.id_namespace, namespace_counter.as_mut()) {
+                    (Some(namespace), Some(counter)) => {
+                        *counter += 1;
+                        format!("{}-{:06}", namespace, counter)
+                    }
+                    _ => format!("{}-{:06}", manufacturer.address, minted),
+                },
+            };
+            product_ids.push(product_id);
+        }
+        if product_ids.iter().any(|product_id| self.products.contains_key(product_id)) {
+            return Err(VeriCharmError::DuplicateProductId);
+        }
         
-        // Verify current owner is the sender
-        if charm_token.current_owner != ctx.sender {
-            return Err(VeriCharmError::NotTokenOwner);
+        for (prefix, counter) in sku_counters {
+            self.sku_counters.insert((manufacturer.address.clone(), prefix), counter);
         }
+        if let (Some(namespace), Some(counter)) = (&manufacturer.id_namespace, namespace_counter) {
+            self.namespace_counters.insert(namespace.clone(), counter);
+        }
+        self.total_minted += product_ids.len() as u64;
+        Ok(product_ids)
+    }
+
+    /// Store freshly minted tokens, assign their serials and update every
+    /// mint-derived record. Manufacturer stats are written once for the whole slice.
+    fn record_mint(&mut self, ctx: &Context, manufacturer: Manufacturer, charm_tokens: &mut [CharmToken]) {
+        let event_tag = manufacturer.event_tag.clone();
         
-        // Check if token is burned
-        if charm_token.burned {
-            return Err(VeriCharmError::TokenBurned);
+        for charm_token in charm_tokens.iter_mut() {
+            let product_id = charm_token.id.clone();
+            
+            // Record the mint in the accumulator for light-client inclusion proofs
+            let leaf_index = self.mint_accumulator.append(charm_token.calculate_verification_hash(), &mut self.mint_tree);
+            self.mint_leaf_index.insert(product_id.clone(), leaf_index);
+            self.mint_sequence.insert(leaf_index, product_id.clone());
+            charm_token.serial = serial_code(
+                &self.serial_salt,
+                &charm_token.manufacturer,
+                leaf_index,
+                &charm_token.product_data.product_signature,
+            );
+            
+            // Store token
+            self.products.insert(product_id.clone(), charm_token.clone());
+            self.index_owner(&charm_token.current_owner, &product_id);
+            
+            if let Some(batch_id) = charm_token.batch_id {
+                let mut members = self.batch_members.get(&batch_id).unwrap_or_default();
+                members.push(product_id.clone());
+                self.batch_members.insert(batch_id, members);
+            }
+            
+            // Track the new warranty exposure
+            let mut liability = self.warranty_liabilities.get(&manufacturer.address).unwrap_or_default();
+            liability.token_count += 1;
+            liability.total_value = liability.total_value
+                .saturating_add(charm_token.product_data.declared_value);
+            self.warranty_liabilities.insert(manufacturer.address.clone(), liability);
+            
+            self.record_holder(&charm_token.current_owner, &manufacturer.address);
+            
+            log!("Charm token minted: {} for product: {}", 
+                 product_id, charm_token.product_data.log_name());
+            self.emit_event(VeriCharmEvent::Minted {
+                product_id,
+                manufacturer: manufacturer.address.clone(),
+                owner: charm_token.current_owner.clone(),
+                event_tag: event_tag.clone(),
+            });
+        }
+        
+        // Update manufacturer stats
+        let address = manufacturer.address.clone();
+        let mut updated_manufacturer = manufacturer;
+        updated_manufacturer.products_minted += charm_tokens.len() as u64;
+        self.manufacturers.insert(address.clone(), updated_manufacturer);
+        
+        // Every mint in this block counts toward the manufacturer's rate limit
+        let minted = match self.mint_windows.get(&address) {
+            Some((block, minted)) if block == ctx.block_height => minted,
+            _ => 0,
+        };
+        self.mint_windows.insert(address, (ctx.block_height, minted.saturating_add(charm_tokens.len() as u32)));
+        
+        self.treasury = self.treasury.saturating_add(ctx.value);
+    }
+
+    /// Walk `reissued_from` links back through replaced tokens
+    fn recall_lineage(&self, charm_token: &CharmToken) -> Vec<ProductId> {
+        let mut lineage = Vec::new();
+        let mut previous = charm_token.reissued_from.clone();
+        while let Some(product_id) = previous {
+            if lineage.len() == MAX_LINEAGE_DEPTH {
+                break;
+            }
+            previous = self.products.get(&product_id)
+                .and_then(|token| token.reissued_from);
+            lineage.push(product_id);
         }
+        lineage
+    }
+
+    /// Configured seconds per block
+    fn block_time(&self) -> u64 {
+        match self.block_time_secs {
+            0 => DEFAULT_BLOCK_TIME_SECS,
+            secs => secs,
+        }
+    }
+
+    /// Manufacturer's default warranty converted to blocks at the configured block time
+    fn warranty_period_blocks(&self, manufacturer: &Manufacturer) -> u64 {
+        let seconds = match manufacturer.default_warranty {
+            0 => WARRANTY_PERIOD_SECS,
+            seconds => seconds,
+        };
+        seconds / self.block_time()
+    }
+
+    /// Update retailer sales counters for a token moving `from` -> `to`
+    fn record_retailer_transfer(&mut self, from: &Address, to: &Address) {
+        if let Some(mut retailer) = self.retailers.get(from) {
+            retailer.tokens_sold += 1;
+            retailer.tokens_held = retailer.tokens_held.saturating_sub(1);
+            self.retailers.insert(from.clone(), retailer);
+        }
+        if let Some(mut retailer) = self.retailers.get(to) {
+            retailer.tokens_received += 1;
+            retailer.tokens_held += 1;
+            self.retailers.insert(to.clone(), retailer);
+        }
+    }
+
+    /// Drop a burned token from its owner's retailer holdings
+    fn record_retailer_burn(&mut self, owner: &Address) {
+        if let Some(mut retailer) = self.retailers.get(owner) {
+            retailer.tokens_held = retailer.tokens_held.saturating_sub(1);
+            self.retailers.insert(owner.clone(), retailer);
+        }
+    }
+
+    /// Move `charm_token` to `new_owner` after all transfer checks have passed
+    fn complete_transfer(
+        &mut self,
+        ctx: &Context,
+        mut charm_token: CharmToken,
+        new_owner: Address,
+        zk_proof: Option<ZkProof>,
+    ) -> TransferReceipt {
+        let product_id = charm_token.id.clone();
         
         // Verify warranty period hasn't expired if transferring from consumer
         let current_time = ctx.block_height;
@@ -141,63 +796,481 @@ impl Contract for VeriCharmContract {
             tx_hash: ctx.tx_hash.clone(),
         };
         
+        let from = charm_token.current_owner.clone();
+        
+        // Secondhand sales either restart the warranty or, if it is
+        // non-transferable, end it
+        if charm_token.metadata.reset_warranty_on_resale && from != charm_token.manufacturer {
+            charm_token.warranty_restart = Some(current_time);
+            self.restore_liability(&mut charm_token);
+        } else if !charm_token.metadata.warranty_transferable && from != charm_token.manufacturer {
+            charm_token.warranty_period = charm_token.warranty_period
+                .min(current_time.saturating_sub(charm_token.warranty_start()));
+            self.release_liability(&mut charm_token);
+        }
+        
         charm_token.transfer_history.push(transfer_record);
         charm_token.current_owner = new_owner.clone();
+        charm_token.co_owners.clear();
+        charm_token.owner_privacy = PrivacyLevel::Public;
+        self.record_retailer_transfer(&from, &new_owner);
+        self.record_holder(&new_owner, &charm_token.manufacturer);
+        self.unindex_owner(&from, &product_id);
+        self.index_owner(&new_owner, &product_id);
+        self.pending_transfers.remove(&product_id);
         charm_token.zk_proof = zk_proof;
         
-        self.products.insert(product_id, charm_token);
+        let receipt = TransferReceipt {
+            product_id: product_id.clone(),
+            from,
+            to: new_owner.clone(),
+            transfer_time: current_time,
+            warranty_remaining_blocks: charm_token.warranty_remaining(current_time),
+            warranty_transferable: charm_token.metadata.warranty_transferable,
+            warranty_active: charm_token.is_in_warranty(current_time),
+        };
+        
+        let event_tag = self.event_tag_for(&charm_token.manufacturer);
+        self.products.insert(product_id.clone(), charm_token);
         
         log!("Charm token {} transferred to {}", product_id, new_owner);
-        Ok(())
+        self.emit_event(VeriCharmEvent::Transferred {
+            product_id,
+            from: receipt.from.clone(),
+            to: new_owner,
+            event_tag,
+        });
+        receipt
     }
 
-    /// Burn Charm token for rewards/raffle entry
-    fn burn_charm(
-        &mut self,
-        ctx: &Context,
-        product_id: ProductId,
-        burn_reason: BurnReason,
-    ) -> Result<BurnReceipt, Self::Error> {
-        let mut charm_token = self.products.get(&product_id)
-            .ok_or(VeriCharmError::ProductNotFound)?;
-        
-        // Verify ownership
-        if charm_token.current_owner != ctx.sender {
-            return Err(VeriCharmError::NotTokenOwner);
+    /// Record that `holder` received a token from `manufacturer`
+    fn record_holder(&mut self, holder: &Address, manufacturer: &Address) {
+        let key = (holder.clone(), manufacturer.clone());
+        let received = self.holder_receipts.get(&key).unwrap_or(0);
+        self.holder_receipts.insert(key, received + 1);
+    }
+
+    /// Whether `holder` has ever received a token from `manufacturer`
+    fn has_held(&self, holder: &Address, manufacturer: &Address) -> bool {
+        self.holder_receipts.contains_key(&(holder.clone(), manufacturer.clone()))
+    }
+
+    /// Distinct addresses that reported `product_id` as counterfeit
+    fn counterfeit_report_count(&self, product_id: &ProductId) -> u32 {
+        self.counterfeit_reports.get(product_id).map_or(0, |reporters| reporters.len() as u32)
+    }
+
+    /// Add `product_id` to `owner`'s bucket in the owner index
+    fn index_owner(&mut self, owner: &Address, product_id: &ProductId) {
+        let mut owned = self.owner_index.get(owner).unwrap_or_default();
+        owned.push(product_id.clone());
+        self.owner_index.insert(owner.clone(), owned);
+    }
+
+    /// Remove `product_id` from `owner`'s bucket in the owner index
+    fn unindex_owner(&mut self, owner: &Address, product_id: &ProductId) {
+        let mut owned = self.owner_index.get(owner).unwrap_or_default();
+        owned.retain(|owned_id| owned_id != product_id);
+        if owned.is_empty() {
+            self.owner_index.remove(owner);
+        } else {
+            self.owner_index.insert(owner.clone(), owned);
         }
-        
-        // Check warranty period has expired
-        if charm_token.is_in_warranty(ctx.block_height) {
-            return Err(VeriCharmError::WarrantyActive);
+    }
+
+    /// Initiated beam that may still complete at block `completed_at`
+    fn completable_beam(&self, beam_id: &BeamId, completed_at: u64) -> Result<CrossChainBeam, VeriCharmError> {
+        let beam = self.beam_records.get(beam_id)
+            .ok_or(VeriCharmError::BeamNotFound)?;
+        if beam.status != BeamStatus::Initiated {
+            return Err(VeriCharmError::InvalidBeamState);
+        }
+        if completed_at > beam.timeout_height {
+            return Err(VeriCharmError::BeamTimedOut);
         }
+        Ok(beam)
+    }
+
+    /// Mark `beam` completed and hand the token to the beam sender
+    fn finish_beam(&mut self, ctx: &Context, mut beam: CrossChainBeam, unlock_tx_hash: Hash) {
+        beam.status = BeamStatus::Completed;
+        beam.unlock_tx_hash = Some(unlock_tx_hash);
         
-        // Mark as burned
-        charm_token.burned = true;
-        self.products.insert(product_id.clone(), charm_token);
+        if let Some(mut charm_token) = self.products.get(&beam.product_id) {
+            charm_token.locked_beam = None;
+            if charm_token.current_owner != beam.sender {
+                charm_token.transfer_history.push(TransferRecord {
+                    from: charm_token.current_owner.clone(),
+                    to: beam.sender.clone(),
+                    timestamp: ctx.block_height,
+                    tx_hash: unlock_tx_hash,
+                });
+                self.unindex_owner(&charm_token.current_owner, &beam.product_id);
+                self.index_owner(&beam.sender, &beam.product_id);
+                charm_token.current_owner = beam.sender.clone();
+            }
+            self.products.insert(beam.product_id.clone(), charm_token);
+        }
         
-        // Generate raffle entry if applicable
-        let raffle_entry = match burn_reason {
-            BurnReason::RaffleEntry => Some(RaffleEntry {
-                participant: ctx.sender.clone(),
-                product_id: product_id.clone(),
-                burn_time: ctx.block_height,
-                entry_id: hash(&[&ctx.sender, &product_id, &ctx.block_height.to_be_bytes()]),
-            }),
-            _ => None,
+        self.emit_event(VeriCharmEvent::BeamCompleted {
+            beam_id: beam.beam_id,
+            product_id: beam.product_id.clone(),
+        });
+        self.beam_records.insert(beam.beam_id, beam);
+    }
+
+    /// Manufacturer a mint by `ctx.sender` is credited to: the sender itself,
+    /// or the manufacturer that delegated to it as an operator
+    fn resolve_minter(&self, ctx: &Context) -> Result<Manufacturer, VeriCharmError> {
+        if let Some(manufacturer) = self.manufacturers.get(&ctx.sender) {
+            return Ok(manufacturer);
+        }
+        let delegator = self.operators.get(&ctx.sender)
+            .ok_or(VeriCharmError::UnauthorizedManufacturer)?;
+        self.manufacturers.get(&delegator)
+            .ok_or(VeriCharmError::NotAuthorizedOperator)
+    }
+
+    /// Reject minting `count` more tokens for `manufacturer` in this block if
+    /// it would exceed `mint_rate_limit`
+    fn check_mint_rate(&self, ctx: &Context, manufacturer: &Address, count: usize) -> Result<(), VeriCharmError> {
+        if self.mint_rate_limit == 0 {
+            return Ok(());
+        }
+        let minted = match self.mint_windows.get(manufacturer) {
+            Some((block, minted)) if block == ctx.block_height => minted as usize,
+            _ => 0,
         };
-        
-        let receipt = BurnReceipt {
-            product_id,
-            burner: ctx.sender.clone(),
-            burn_time: ctx.block_height,
-            raffle_entry,
+        if minted + count > self.mint_rate_limit as usize {
+            return Err(VeriCharmError::RateLimited);
+        }
+        Ok(())
+    }
+
+    /// Hold a transfer of `charm_token` as an offer for `new_owner` to accept
+    fn offer_transfer(
+        &mut self,
+        ctx: &Context,
+        charm_token: &CharmToken,
+        new_owner: Address,
+        zk_proof: Option<ZkProof>,
+    ) -> PendingTransfer {
+        let offer = PendingTransfer {
+            product_id: charm_token.id.clone(),
+            from: charm_token.current_owner.clone(),
+            to: new_owner,
+            offered_at: ctx.block_height,
+            zk_proof,
+            attestation: None,
         };
+        self.pending_transfers.insert(offer.product_id.clone(), offer.clone());
         
-        log!("Charm token burned for raffle entry by {}", ctx.sender);
-        Ok(receipt)
+        log!("Charm token {} offered to {}", offer.product_id, offer.to);
+        self.emit_event(VeriCharmEvent::TransferOffered {
+            product_id: offer.product_id.clone(),
+            from: offer.from.clone(),
+            to: offer.to.clone(),
+        });
+        offer
     }
 
-    /// Verify product authenticity using ZK proofs
+    /// Verify a product and burn the nullifier of a proof that verified. Every
+    /// mutating verification path goes through here so proofs can't be replayed.
+    fn verify_and_consume(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        verification_data: VerificationData,
+    ) -> Result<VerificationResult, VeriCharmError> {
+        let nullifier = verification_data.zk_proof.as_ref().and(verification_data.nullifier);
+        let result = self.verify_product(ctx, product_id, verification_data)?;
+        
+        if let Some(nullifier) = nullifier {
+            if !result.zk_skipped && result.authenticity_reason != Some(AuthenticityFailure::ProofInvalid) {
+                self.used_nullifiers.insert(nullifier, true);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Contract implementation
+impl<S: StorageBackend> Contract for VeriCharmContract<S> {
+    type Error = VeriCharmError;
+
+    /// Initialize contract with manufacturer
+    fn init(&mut self, ctx: &Context) -> Result<(), Self::Error> {
+        // State from before `admin` existed loads with no admin; it was
+        // initialized all the same, so don't let the first caller claim it
+        let populated = self.total_minted > 0 || self.manufacturers.iter().next().is_some();
+        if self.admin.is_some() || populated {
+            return Err(VeriCharmError::AlreadyInitialized);
+        }
+        
+        // Ensure only authorized manufacturers can initialize
+        let manufacturer = Manufacturer::new(
+            ctx.sender.clone(),
+            String::from("Initial Manufacturer"),
+            true,
+        );
+        
+        self.manufacturers.insert(ctx.sender.clone(), manufacturer);
+        self.manufacturer_count += 1;
+        self.admin = Some(ctx.sender.clone());
+        
+        // Initialize ZK circuit parameters
+        self.circuit_params = VerificationCircuit::default();
+        self.serial_salt = crypto::canonical_hash(&[
+            ctx.sender.as_ref(),
+            &ctx.tx_hash[..],
+            &ctx.block_height.to_be_bytes(),
+        ]);
+        
+        log!("Veri-Charm contract initialized by {}", ctx.sender);
+        Ok(())
+    }
+
+    /// Mint a new Charm token for a physical product
+    #[payable]
+    fn mint_charm(
+        &mut self, 
+        ctx: &Context,
+        product_data: ProductData,
+        metadata: TokenMetadata,
+        mint_to: Option<Address>,
+    ) -> Result<CharmToken, Self::Error> {
+        self.ensure_not_paused()?;
+        product_data.validate()?;
+        if let Some(recipient) = &mint_to {
+            self.validate_address(ctx, recipient)?;
+        }
+        
+        // Verify caller is a registered manufacturer or one of its operators
+        let manufacturer = self.resolve_minter(ctx)?;
+        
+        if !manufacturer.verified {
+            return Err(VeriCharmError::ManufacturerNotVerified);
+        }
+
+        self.check_mint_rate(ctx, &manufacturer.address, 1)?;
+        let digest = self.verify_product_signature(ctx, &manufacturer, &product_data)?;
+
+        // Charge the volume-adjusted mint fee
+        let fee = effective_mint_fee(self.mint_fee, &self.fee_tiers, manufacturer.products_minted);
+        if ctx.value < fee {
+            return Err(VeriCharmError::InsufficientFee);
+        }
+
+        // Generate unique product ID
+        let product_id = self.next_product_id(&manufacturer, &product_data)?;
+        self.used_product_digests.insert(digest, true);
+        
+        // Create Charm token
+        let mut charm_token = CharmToken::new(
+            product_id,
+            manufacturer.address.clone(),
+            product_data,
+            metadata,
+            ctx.block_height,
+            self.warranty_period_blocks(&manufacturer),
+        );
+        
+        // Minting straight into a distributor's custody records the hand-off
+        if let Some(recipient) = mint_to {
+            charm_token.transfer_history.push(TransferRecord {
+                from: manufacturer.address.clone(),
+                to: recipient.clone(),
+                timestamp: ctx.block_height,
+                tx_hash: ctx.tx_hash.clone(),
+            });
+            self.record_retailer_transfer(&manufacturer.address, &recipient);
+            charm_token.current_owner = recipient;
+        }
+
+        self.record_mint(ctx, manufacturer, core::slice::from_mut(&mut charm_token));
+        
+        Ok(charm_token)
+    }
+
+    /// Transfer Charm token to new owner (retailer or consumer). With
+    /// `first_contact_consent` on, a recipient who has never held this
+    /// manufacturer's tokens gets an offer to accept instead.
+    fn transfer_charm(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        new_owner: Address,
+        zk_proof: Option<ZkProof>,
+    ) -> Result<TransferOutcome, Self::Error> {
+        self.ensure_not_paused()?;
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        self.check_transfer(ctx, &charm_token, &new_owner)?;
+        
+        // Anti-dusting: first-time recipients must consent before they hold it
+        if self.first_contact_consent && !self.has_held(&new_owner, &charm_token.manufacturer) {
+            return Ok(TransferOutcome::Offered(self.offer_transfer(ctx, &charm_token, new_owner, zk_proof)));
+        }
+        
+        Ok(TransferOutcome::Completed(self.complete_transfer(ctx, charm_token, new_owner, zk_proof)))
+    }
+
+    /// Check whether `transfer_charm` would succeed, without transferring.
+    /// Lets wallets explain why a transfer is unavailable.
+    fn transfer_preflight(
+        &self,
+        ctx: &Context,
+        product_id: ProductId,
+        new_owner: Address,
+    ) -> Result<(), TransferBlock> {
+        if self.paused {
+            return Err(TransferBlock::Paused);
+        }
+        let charm_token = self.products.get(&product_id)
+            .ok_or(TransferBlock::ProductNotFound)?;
+        
+        self.check_transfer(ctx, &charm_token, &new_owner)
+    }
+
+    /// Transfer several tokens to one owner, e.g. stock moving between
+    /// retailers. Every token is checked up front and the batch fails as a
+    /// whole if any of them cannot move.
+    fn batch_transfer(
+        &mut self,
+        ctx: &Context,
+        ids: Vec<ProductId>,
+        new_owner: Address,
+    ) -> Result<Vec<ProductId>, Self::Error> {
+        self.ensure_not_paused()?;
+        if ids.len() > MAX_BATCH_TRANSFER {
+            return Err(VeriCharmError::BatchTooLarge);
+        }
+        
+        let mut seen = BTreeSet::new();
+        let mut charm_tokens = Vec::with_capacity(ids.len());
+        for product_id in &ids {
+            if !seen.insert(product_id.clone()) {
+                return Err(VeriCharmError::DuplicateBatchItem);
+            }
+            let charm_token = self.products.get(product_id)
+                .ok_or(VeriCharmError::ProductNotFound)?;
+            self.check_transfer(ctx, &charm_token, &new_owner)?;
+            
+            // Offers are per token, so a batch can't wait on the recipient
+            if self.first_contact_consent && !self.has_held(&new_owner, &charm_token.manufacturer) {
+                return Err(VeriCharmError::RecipientConsentRequired);
+            }
+            charm_tokens.push(charm_token);
+        }
+        
+        for charm_token in charm_tokens {
+            self.complete_transfer(ctx, charm_token, new_owner.clone(), None);
+        }
+        
+        log!("Batch transferred {} tokens to {}", ids.len(), new_owner);
+        Ok(ids)
+    }
+
+    /// Burn Charm token for rewards/raffle entry
+    fn burn_charm(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        burn_reason: BurnReason,
+    ) -> Result<BurnReceipt, Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        if charm_token.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        
+        if charm_token.locked_beam.is_some() {
+            return Err(VeriCharmError::TokenLocked);
+        }
+        
+        if charm_token.parent.is_some() {
+            return Err(VeriCharmError::TokenBundled);
+        }
+        if !charm_token.children.is_empty() {
+            return Err(VeriCharmError::BundleNotEmpty);
+        }
+        
+        if let BurnReason::Expired = burn_reason {
+            // Anyone may clean up an expired perishable; warranty no longer matters
+            if !charm_token.is_expired(ctx.block_height) {
+                return Err(VeriCharmError::NotExpired);
+            }
+        } else {
+            // Verify ownership
+            if !charm_token.is_owned_by(&ctx.sender) {
+                return Err(VeriCharmError::NotTokenOwner);
+            }
+            
+            // Check warranty period has expired; recalled products can
+            // always be returned
+            let recall_return = charm_token.recalled && burn_reason == BurnReason::ProductReturn;
+            if !recall_return && charm_token.is_in_warranty(ctx.block_height) {
+                return Err(VeriCharmError::WarrantyActive);
+            }
+        }
+        
+        // Generate raffle entry if applicable
+        let raffle_entry = match burn_reason {
+            BurnReason::RaffleEntry => Some(RaffleEntry {
+                participant: ctx.sender.clone(),
+                product_id: product_id.clone(),
+                burn_time: ctx.block_height,
+                entry_id: crypto::canonical_hash(&[
+                    ctx.sender.as_ref(),
+                    product_id.as_bytes(),
+                    &ctx.block_height.to_be_bytes(),
+                ]),
+                weight: 1,
+            }),
+            _ => None,
+        };
+        
+        if raffle_entry.as_ref().is_some_and(|entry| self.raffle_entries.contains_key(&entry.entry_id)) {
+            return Err(VeriCharmError::DuplicateRaffleEntry);
+        }
+        
+        // Mark as burned
+        charm_token.burned = true;
+        self.total_burned += 1;
+        self.release_liability(&mut charm_token);
+        self.record_retailer_burn(&charm_token.current_owner);
+        let event_tag = self.event_tag_for(&charm_token.manufacturer);
+        self.products.insert(product_id.clone(), charm_token);
+        self.cleanup_token_associations(&product_id);
+        
+        if let Some(entry) = &raffle_entry {
+            self.raffle_entries.insert(entry.entry_id, entry.clone());
+            self.open_raffle_entries += 1;
+        }
+        
+        let receipt = BurnReceipt {
+            product_id: product_id.clone(),
+            burner: ctx.sender.clone(),
+            burn_time: ctx.block_height,
+            reason: burn_reason.clone(),
+            raffle_entry,
+        };
+        self.burn_receipts.insert(receipt.product_id.clone(), receipt.clone());
+        
+        log!("Charm token burned for raffle entry by {}", ctx.sender);
+        self.emit_event(VeriCharmEvent::Burned {
+            product_id,
+            burner: ctx.sender.clone(),
+            reason: burn_reason,
+            event_tag,
+        });
+        Ok(receipt)
+    }
+
+    /// Verify product authenticity using ZK proofs
     fn verify_product(
         &self,
         ctx: &Context,
@@ -207,36 +1280,134 @@ impl Contract for VeriCharmContract {
         let charm_token = self.products.get(&product_id)
             .ok_or(VeriCharmError::ProductNotFound)?;
         
+        // In safe mode proofs are ignored and trust rests on signatures and provenance
+        let zk_proof = if self.zk_disabled {
+            None
+        } else {
+            verification_data.zk_proof.as_ref()
+        };
+        
+        // Each proof is single-use, tracked by a nullifier among its public inputs
+        if zk_proof.is_some() {
+            let nullifier = verification_data.nullifier
+                .filter(|nullifier| verification_data.public_inputs.contains(nullifier))
+                .ok_or(VeriCharmError::MissingNullifier)?;
+            if self.used_nullifiers.contains_key(&nullifier) {
+                return Err(VeriCharmError::ProofAlreadyUsed);
+            }
+        }
+        
         // If ZK proof provided, verify it
-        if let Some(zk_proof) = &verification_data.zk_proof {
-            let is_valid = self.circuit_params.verify_proof(
+        let proof_valid = match zk_proof {
+            Some(zk_proof) => self.circuit_params.verify_proof(
                 zk_proof,
                 &verification_data.public_inputs,
-            )?;
-            
-            if !is_valid {
-                return Err(VeriCharmError::InvalidProof);
-            }
-        }
+            )?,
+            None => true,
+        };
         
         // Verify manufacturer signature
         let manufacturer = self.manufacturers.get(&charm_token.manufacturer)
             .ok_or(VeriCharmError::ManufacturerNotFound)?;
         
-        if !manufacturer.verified {
-            return Err(VeriCharmError::ManufacturerNotVerified);
-        }
+        // Check the physical NFC tag signature against the key registered at mint
+        let physical_tag_verified = match (
+            &verification_data.tag_signature,
+            &charm_token.product_data.tag_public_key,
+        ) {
+            (Some(signature), Some(public_key)) => {
+                // The tag must sign the token's current state under a recent
+                // block, so a recorded scan can't be replayed later
+                let challenge_age = ctx.block_height.checked_sub(verification_data.tag_challenge_block);
+                if verification_data.verification_hash != charm_token.calculate_verification_hash()
+                    || challenge_age.map_or(true, |age| age > TAG_SCAN_WINDOW_BLOCKS)
+                {
+                    return Err(VeriCharmError::InvalidTagSignature);
+                }
+                let message = crypto::tag_message(
+                    &product_id,
+                    &verification_data.verification_hash,
+                    verification_data.tag_challenge_block,
+                );
+                if !crypto::verify_signature(public_key, &message, signature) {
+                    return Err(VeriCharmError::InvalidTagSignature);
+                }
+                true
+            }
+            _ => false,
+        };
         
         // Check supply chain integrity
-        let is_supply_chain_valid = charm_token.verify_supply_chain();
+        let is_supply_chain_valid = charm_token
+            .verify_supply_chain_with_proof(&verification_data.provenance_proof)?;
+        
+        // Report the first failed check rather than erroring, so callers get
+        // one structured answer
+        let authenticity_reason = if !proof_valid {
+            Some(AuthenticityFailure::ProofInvalid)
+        } else if manufacturer.revoked {
+            Some(AuthenticityFailure::ManufacturerRevoked)
+        } else if !manufacturer.verified {
+            Some(AuthenticityFailure::ManufacturerUnverified)
+        } else if !is_supply_chain_valid {
+            Some(AuthenticityFailure::SupplyChainBroken)
+        } else if charm_token.recalled {
+            Some(AuthenticityFailure::Recalled)
+        } else {
+            None
+        };
+        let is_authentic = authenticity_reason.is_none();
+        
+        // A separate proof under the manufacturer's own circuit, bound to the
+        // token's current state, lets the brand vouch for a positive result
+        let verification_hash = charm_token.calculate_verification_hash();
+        let manufacturer_attestation = match (
+            &verification_data.manufacturer_proof,
+            self.manufacturer_circuits.get(&charm_token.manufacturer),
+        ) {
+            (Some(manufacturer_proof), Some(circuit))
+                if is_authentic
+                    && verification_data.manufacturer_public_inputs
+                        .contains(&verification::hash_to_field(&verification_hash))
+                    && circuit.verify_proof(
+                        manufacturer_proof,
+                        &verification_data.manufacturer_public_inputs,
+                    )? =>
+            {
+                Some(circuit.attest(&product_id, &verification_hash, manufacturer_proof))
+            }
+            _ => None,
+        };
+        
+        let confidence_score = verification::confidence_score(&ConfidenceSignals {
+            provenance_continuous: is_supply_chain_valid,
+            zk_proof_valid: zk_proof.is_some() && proof_valid,
+            physical_tag_verified,
+            manufacturer_verified: manufacturer.verified,
+            counterfeit_reports: self.counterfeit_report_count(&product_id),
+        });
         
         Ok(VerificationResult {
             product_id,
-            is_authentic: is_supply_chain_valid,
+            is_authentic,
+            authenticity_reason,
             manufacturer: charm_token.manufacturer.clone(),
-            current_owner: charm_token.current_owner.clone(),
+            current_owner: OwnerView::render(
+                &charm_token.current_owner,
+                &charm_token.id,
+                &charm_token.owner_privacy,
+            ),
             warranty_valid: charm_token.is_in_warranty(ctx.block_height),
             verification_time: ctx.block_height,
+            physical_tag_verified,
+            recall_lineage: self.recall_lineage(&charm_token),
+            average_holding_blocks: charm_token.average_holding_blocks(ctx.block_height),
+            manufacturer_attested: manufacturer_attestation.is_some(),
+            manufacturer_attestation,
+            confidence_score,
+            zk_skipped: self.zk_disabled,
+            recall_reason: charm_token.recall_reason.clone(),
+            route_valid: charm_token.verify_route(),
         })
     }
 
@@ -248,18 +1419,76 @@ impl Contract for VeriCharmContract {
         target_chain: ChainId,
         beam_data: BeamData,
     ) -> Result<BeamReceipt, Self::Error> {
+        self.ensure_not_paused()?;
         // Verify token exists and is owned by sender
-        let charm_token = self.products.get(&product_id)
+        let mut charm_token = self.products.get(&product_id)
             .ok_or(VeriCharmError::ProductNotFound)?;
         
         if charm_token.current_owner != ctx.sender {
             return Err(VeriCharmError::NotTokenOwner);
         }
         
+        if target_chain == ctx.chain_id {
+            return Err(VeriCharmError::SameChainBeam);
+        }
+        
+        // A beam moves the token like a transfer, so the same state checks apply
+        if charm_token.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        if charm_token.frozen {
+            return Err(VeriCharmError::TokenFrozen);
+        }
+        if charm_token.blacklisted {
+            return Err(VeriCharmError::TokenBlacklisted);
+        }
+        if charm_token.recalled {
+            return Err(VeriCharmError::ProductRecalled);
+        }
+        if charm_token.parent.is_some() {
+            return Err(VeriCharmError::TokenBundled);
+        }
+        if !charm_token.children.is_empty() {
+            return Err(VeriCharmError::BundleNotEmpty);
+        }
+        
+        // Only a timed-out beam's lock may be superseded by a retry
+        if let Some(locked_id) = &charm_token.locked_beam {
+            let lock_expired = self.beam_records.get(locked_id).is_some_and(|beam| {
+                beam.status == BeamStatus::Initiated && ctx.block_height > beam.timeout_height
+            });
+            if !lock_expired {
+                return Err(VeriCharmError::TokenLocked);
+            }
+        }
+        
+        // Retrying after an expired beam counts against the attempt limit
+        let max_attempts = match self.max_beam_attempts {
+            0 => DEFAULT_MAX_BEAM_ATTEMPTS,
+            limit => limit,
+        };
+        let mut attempt = 1;
+        if let Some(previous_id) = self.latest_beam.get(&product_id) {
+            if let Some(mut previous) = self.beam_records.get(&previous_id) {
+                let timed_out = previous.status == BeamStatus::Initiated
+                    && ctx.block_height > previous.timeout_height;
+                if timed_out || previous.status == BeamStatus::Cancelled {
+                    attempt = previous.attempt + 1;
+                    if attempt > max_attempts {
+                        return Err(VeriCharmError::BeamRetriesExhausted);
+                    }
+                }
+                if timed_out {
+                    previous.status = BeamStatus::Expired;
+                    self.beam_records.insert(previous_id, previous);
+                }
+            }
+        }
+        
         // Create beam record
-        let beam_id = hash(&[
-            &product_id,
-            &target_chain,
+        let beam_id = crypto::canonical_hash(&[
+            product_id.as_bytes(),
+            target_chain.as_bytes(),
             &ctx.block_height.to_be_bytes(),
         ]);
         
@@ -273,25 +1502,2676 @@ impl Contract for VeriCharmContract {
             status: BeamStatus::Initiated,
             lock_tx_hash: None,
             unlock_tx_hash: None,
+            timeout_height: ctx.block_height + BEAM_TIMEOUT_BLOCKS,
+            attempt,
+            max_attempts,
+            payload_version: BEAM_PAYLOAD_VERSION,
         };
         
         self.beam_records.insert(beam_id.clone(), beam_record);
+        self.latest_beam.insert(product_id.clone(), beam_id.clone());
+        charm_token.locked_beam = Some(beam_id.clone());
+        self.products.insert(product_id.clone(), charm_token);
         
         // Generate lock transaction for source chain
         let lock_script = generate_lock_script(&beam_id, &target_chain);
         
         log!("Cross-chain beam initiated for {} to {}", product_id, target_chain);
+        self.emit_event(VeriCharmEvent::BeamInitiated {
+            beam_id: beam_id.clone(),
+            product_id,
+            target_chain,
+        });
         
         Ok(BeamReceipt {
             beam_id,
             lock_script,
-            timeout_height: ctx.block_height + 100, // 100 blocks to complete
+            timeout_height: ctx.block_height + BEAM_TIMEOUT_BLOCKS,
         })
     }
-}
 
-// Entry point for WASM compilation
-#[no_mangle]
-pub extern "C" fn _start() {
-    contract::run(VeriCharmContract::default());
+    /// Current root of the mint accumulator
+    fn mint_root(&self) -> Hash {
+        self.mint_accumulator.root()
+    }
+
+    /// Inclusion proof of a product's mint-time verification hash against `mint_root`
+    fn mint_inclusion_proof(&self, product_id: ProductId) -> Result<MerkleProof, Self::Error> {
+        let leaf_index = self.mint_leaf_index.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        self.mint_accumulator.proof(leaf_index, &self.mint_tree)
+            .ok_or(VeriCharmError::ProductNotFound)
+    }
+
+    /// Recompute a manufacturer's `products_minted` from the tokens they minted.
+    ///
+    /// Walks `mint_sequence`, so only minted tokens count (not split children
+    /// or settled beams) and tokens minted while the scan is in progress are
+    /// still reached. Scans at most `MAX_RECOMPUTE_BATCH` mints per call.
+    /// Returns `true` once the scan has finished and the counter was
+    /// rewritten; call again while it returns `false` to resume from the
+    /// stored cursor.
+    fn recompute_manufacturer_stats(
+        &mut self,
+        ctx: &Context,
+        manufacturer: Address,
+    ) -> Result<bool, Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+
+        let mut record = self.manufacturers.get(&manufacturer)
+            .ok_or(VeriCharmError::ManufacturerNotFound)?;
+
+        let progress = self.stats_recompute.get(&manufacturer);
+        let mut count = progress.as_ref().map_or(0, |(_, count)| *count);
+        let cursor = progress.map(|(cursor, _)| cursor);
+
+        let next = paginate_process(&self.mint_sequence, cursor.as_ref(), MAX_RECOMPUTE_BATCH, |_, product_id| {
+            if self.products.get(&product_id).is_some_and(|token| token.manufacturer == manufacturer) {
+                count += 1;
+            }
+        });
+
+        if let Some(next) = next {
+            self.stats_recompute.insert(manufacturer, (next, count));
+            return Ok(false);
+        }
+
+        self.stats_recompute.remove(&manufacturer);
+        record.products_minted = count;
+        self.manufacturers.insert(manufacturer.clone(), record);
+
+        log!("Manufacturer stats recomputed for {}: {} products", manufacturer, count);
+        Ok(true)
+    }
+
+    /// Register or remove a regulatory auditor (admin only)
+    fn set_auditor(&mut self, ctx: &Context, auditor: Address, enabled: bool) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        
+        if enabled {
+            self.auditors.insert(auditor.clone(), true);
+        } else {
+            self.auditors.remove(&auditor);
+        }
+        
+        log!("Auditor {} enabled: {}", auditor, enabled);
+        Ok(())
+    }
+
+    /// Verify a product and append the result to the tamper-evident audit log
+    fn regulatory_verify(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        verification_data: VerificationData,
+    ) -> Result<(VerificationResult, AuditId), Self::Error> {
+        self.ensure_not_paused()?;
+        if !self.auditors.contains_key(&ctx.sender) {
+            return Err(VeriCharmError::NotAuditor);
+        }
+        
+        let result = self.verify_and_consume(ctx, product_id.clone(), verification_data)?;
+        
+        let audit_id = self.audit_count;
+        let mut record = AuditRecord {
+            audit_id,
+            auditor: ctx.sender.clone(),
+            product_id: product_id.clone(),
+            block_height: ctx.block_height,
+            result: result.clone(),
+            prev_hash: self.audit_head,
+            record_hash: [0u8; 32],
+        };
+        record.record_hash = record.compute_hash();
+        
+        self.audit_head = record.record_hash;
+        self.audit_count += 1;
+        self.audit_log.insert(audit_id, record);
+        
+        log!("Regulatory verification {} of {} by {}", audit_id, product_id, ctx.sender);
+        Ok((result, audit_id))
+    }
+
+    /// Fetch a regulatory audit record
+    fn get_audit_record(&self, audit_id: AuditId) -> Result<AuditRecord, Self::Error> {
+        self.audit_log.get(&audit_id)
+            .ok_or(VeriCharmError::AuditRecordNotFound)
+    }
+
+    /// Set the maximum beam attempts per product (admin only)
+    fn set_max_beam_attempts(&mut self, ctx: &Context, max_attempts: u32) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.max_beam_attempts = max_attempts;
+        Ok(())
+    }
+
+    /// Fetch a Charm token by product id. Unless the owner is public, callers
+    /// other than the owner or a co-owner get it with ownership cleared.
+    fn get_charm(&self, ctx: &Context, product_id: ProductId) -> Result<CharmToken, Self::Error> {
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        if charm_token.owner_privacy == PrivacyLevel::Public || charm_token.is_owned_by(&ctx.sender) {
+            return Ok(charm_token);
+        }
+        Ok(charm_token.redacted())
+    }
+
+    /// Whether a product id has been minted, without loading the token
+    fn exists(&self, product_id: ProductId) -> bool {
+        self.products.contains_key(&product_id)
+    }
+
+    /// Nonce the next signed operation in `signer`'s sequence must use. Oracle
+    /// attestations use the sequence of the token's manufacturer.
+    fn signer_nonce(&self, signer: Address) -> u64 {
+        self.signer_nonces.get(&signer).unwrap_or(0)
+    }
+
+    /// Set how recently tokens must have been verified before they can be transferred
+    fn set_verification_freshness(
+        &mut self,
+        ctx: &Context,
+        max_age_blocks: Option<u64>,
+    ) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut manufacturer = self.manufacturers.get(&ctx.sender)
+            .ok_or(VeriCharmError::UnauthorizedManufacturer)?;
+        
+        manufacturer.require_recent_verification = max_age_blocks;
+        self.manufacturers.insert(ctx.sender.clone(), manufacturer);
+        Ok(())
+    }
+
+    /// Verify a product and record the verification block on the token
+    fn verify_product_mut(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        verification_data: VerificationData,
+    ) -> Result<VerificationResult, Self::Error> {
+        self.ensure_not_paused()?;
+        let result = self.verify_and_consume(ctx, product_id.clone(), verification_data)?;
+        
+        if result.is_authentic {
+            let mut charm_token = self.products.get(&product_id)
+                .ok_or(VeriCharmError::ProductNotFound)?;
+            charm_token.last_verified_block = Some(ctx.block_height);
+            self.products.insert(product_id, charm_token);
+        }
+        
+        Ok(result)
+    }
+
+    /// Transfer part of a bulk token's quantity by splitting off a child token
+    fn transfer_quantity(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        new_owner: Address,
+        amount: u64,
+    ) -> Result<CharmToken, Self::Error> {
+        self.ensure_not_paused()?;
+        let mut parent = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        self.check_transfer(ctx, &parent, &new_owner)?;
+        
+        // A bundle's quantity is its children's; splitting it would duplicate them
+        if !parent.children.is_empty() {
+            return Err(VeriCharmError::BundleNotEmpty);
+        }
+        
+        // A split can't be offered, so first-time recipients must accept a
+        // whole-token transfer from this manufacturer first
+        if self.first_contact_consent && !self.has_held(&new_owner, &parent.manufacturer) {
+            return Err(VeriCharmError::RecipientConsentRequired);
+        }
+        
+        let available = parent.quantity.ok_or(VeriCharmError::NotDivisible)?;
+        if amount == 0 || amount > available {
+            return Err(VeriCharmError::InsufficientQuantity);
+        }
+        
+        parent.split_count += 1;
+        parent.quantity = Some(available - amount);
+        
+        let mut child = parent.clone();
+        child.id = format!("{}/{}", product_id, parent.split_count);
+        child.current_owner = new_owner.clone();
+        child.quantity = Some(amount);
+        child.split_count = 0;
+        child.split_from = Some(product_id.clone());
+        child.children = Vec::new();
+        child.parent = None;
+        // The split-off units get their own printable serial
+        child.serial = serial_code(
+            &self.serial_salt,
+            &child.manufacturer,
+            parent.split_count,
+            child.id.as_bytes(),
+        );
+        child.co_owners.clear();
+        child.owner_privacy = PrivacyLevel::Public;
+        // The parent's declared value already covers the whole quantity
+        child.liability_released = true;
+        child.transfer_history.push(TransferRecord {
+            from: parent.current_owner.clone(),
+            to: new_owner.clone(),
+            timestamp: ctx.block_height,
+            tx_hash: ctx.tx_hash.clone(),
+        });
+        
+        // The sender sells the split-off units but keeps holding the parent
+        if let Some(mut retailer) = self.retailers.get(&parent.current_owner) {
+            retailer.tokens_sold += 1;
+            self.retailers.insert(parent.current_owner.clone(), retailer);
+        }
+        if let Some(mut retailer) = self.retailers.get(&new_owner) {
+            retailer.tokens_received += 1;
+            retailer.tokens_held += 1;
+            self.retailers.insert(new_owner.clone(), retailer);
+        }
+        self.record_holder(&new_owner, &child.manufacturer);
+        self.products.insert(product_id.clone(), parent);
+        self.products.insert(child.id.clone(), child.clone());
+        self.index_owner(&new_owner, &child.id);
+        self.total_derived += 1;
+        
+        // Splits share the parent's batch, so a batch recall must reach them
+        if let Some(batch_id) = child.batch_id {
+            let mut members = self.batch_members.get(&batch_id).unwrap_or_default();
+            members.push(child.id.clone());
+            self.batch_members.insert(batch_id, members);
+        }
+        
+        log!("Split {} units of {} into {} for {}", amount, product_id, child.id, new_owner);
+        self.emit_event(VeriCharmEvent::Split {
+            parent_id: product_id,
+            child_id: child.id.clone(),
+            to: new_owner,
+            amount,
+        });
+        Ok(child)
+    }
+
+    /// Set the trusted manufacturer registry root (admin only)
+    fn set_trust_registry_root(&mut self, ctx: &Context, root: Hash) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.trust_registry_root = Some(root);
+        Ok(())
+    }
+
+    /// Mark a manufacturer verified by proving its inclusion in the trusted registry.
+    /// The leaf is `sha256(address)`; admin verification remains available as a fallback.
+    fn verify_manufacturer_via_registry(
+        &mut self,
+        _ctx: &Context,
+        target: Address,
+        registry_proof: MerkleProof,
+    ) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let root = self.trust_registry_root
+            .ok_or(VeriCharmError::RegistryNotConfigured)?;
+        
+        let mut manufacturer = self.manufacturers.get(&target)
+            .ok_or(VeriCharmError::ManufacturerNotFound)?;
+        if manufacturer.revoked {
+            return Err(VeriCharmError::ManufacturerRevoked);
+        }
+        
+        let leaf: Hash = Sha256::digest(target.as_ref()).into();
+        if registry_proof.leaf != leaf || !registry_proof.verify(&root) {
+            return Err(VeriCharmError::InvalidRegistryProof);
+        }
+        
+        manufacturer.verified = true;
+        self.manufacturers.insert(target.clone(), manufacturer);
+        
+        log!("Manufacturer {} verified via trust registry", target);
+        Ok(())
+    }
+
+    /// Set which events are emitted (admin only)
+    fn set_event_verbosity(&mut self, ctx: &Context, verbosity: EventVerbosity) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.event_verbosity = verbosity;
+        Ok(())
+    }
+
+    /// Set how many distinct counterfeit reports auto-freeze a token (admin only)
+    fn set_auto_freeze_threshold(&mut self, ctx: &Context, threshold: u32) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.auto_freeze_report_threshold = threshold;
+        Ok(())
+    }
+
+    /// Report a token as a suspected counterfeit. Once enough distinct
+    /// addresses report it, the token is frozen pending manufacturer review.
+    fn report_counterfeit(&mut self, ctx: &Context, product_id: ProductId) -> Result<u32, Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        
+        let mut reporters = self.counterfeit_reports.get(&product_id).unwrap_or_default();
+        if reporters.contains(&ctx.sender) {
+            return Err(VeriCharmError::AlreadyReported);
+        }
+        reporters.push(ctx.sender.clone());
+        let report_count = reporters.len() as u32;
+        self.counterfeit_reports.insert(product_id.clone(), reporters);
+        
+        log!("Counterfeit report {} for {} by {}", report_count, product_id, ctx.sender);
+        
+        let threshold = self.auto_freeze_report_threshold;
+        if threshold > 0 && report_count >= threshold && !charm_token.frozen {
+            charm_token.frozen = true;
+            self.products.insert(product_id.clone(), charm_token);
+            self.emit_event(VeriCharmEvent::TokenAutoFrozen { product_id, report_count });
+        }
+        
+        Ok(report_count)
+    }
+
+    /// Lift a freeze after review (manufacturer only)
+    fn unfreeze_token(&mut self, ctx: &Context, product_id: ProductId) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        if charm_token.manufacturer != ctx.sender {
+            return Err(VeriCharmError::UnauthorizedManufacturer);
+        }
+        
+        // Escalated disputes are decided by the arbitrator, not the manufacturer
+        if self.disputes.contains_key(&product_id) {
+            return Err(VeriCharmError::DisputeEscalated);
+        }
+        
+        charm_token.frozen = false;
+        self.products.insert(product_id.clone(), charm_token);
+        
+        log!("Charm token {} unfrozen by {}", product_id, ctx.sender);
+        Ok(())
+    }
+
+    /// Export every live token the caller owns with its verification hash
+    fn export_owner_bundle(&self, ctx: &Context) -> OwnerBundle {
+        let mut entries: Vec<(ProductId, Hash)> = self.owner_index.get(&ctx.sender)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|product_id| {
+                let token = self.products.get(&product_id)?;
+                (token.current_owner == ctx.sender && !token.burned)
+                    .then(|| (product_id, token.calculate_verification_hash()))
+            })
+            .collect();
+        entries.sort();
+        
+        let mut bundle = OwnerBundle {
+            owner: ctx.sender.clone(),
+            chain_id: ctx.chain_id.clone(),
+            contract: ctx.contract_address.clone(),
+            block_height: ctx.block_height,
+            entries,
+            checksum: [0u8; 32],
+        };
+        bundle.checksum = bundle.compute_checksum();
+        bundle
+    }
+
+    /// Register the caller as an unverified manufacturer with the key that
+    /// signs their product data
+    fn register_manufacturer(&mut self, ctx: &Context, name: String, public_key: PublicKey) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.insert_manufacturer(ctx, ctx.sender.clone(), name, public_key)
+    }
+
+    /// Set or rotate the caller's product signing key
+    fn set_manufacturer_key(&mut self, ctx: &Context, public_key: PublicKey) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut manufacturer = self.manufacturers.get(&ctx.sender)
+            .ok_or(VeriCharmError::UnauthorizedManufacturer)?;
+        
+        manufacturer.public_key = Some(public_key);
+        self.manufacturers.insert(ctx.sender.clone(), manufacturer);
+        
+        log!("Product signing key set for {}", ctx.sender);
+        Ok(())
+    }
+
+    /// Appoint the dispute arbitrator (admin only)
+    fn set_arbitrator(&mut self, ctx: &Context, arbitrator: Address) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.validate_address(ctx, &arbitrator)?;
+        self.arbitrator = Some(arbitrator);
+        Ok(())
+    }
+
+    /// Escalate a counterfeit report to the arbitrator (reporters only)
+    fn escalate_dispute(&mut self, ctx: &Context, product_id: ProductId) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let reporters = self.counterfeit_reports.get(&product_id).unwrap_or_default();
+        if !reporters.contains(&ctx.sender) {
+            return Err(VeriCharmError::NotReporter);
+        }
+        
+        if self.disputes.contains_key(&product_id) {
+            return Err(VeriCharmError::DisputeEscalated);
+        }
+        
+        let dispute = Dispute {
+            product_id: product_id.clone(),
+            reporter: ctx.sender.clone(),
+            escalated_at: ctx.block_height,
+            ruling: None,
+        };
+        self.disputes.insert(product_id.clone(), dispute);
+        
+        log!("Dispute over {} escalated by {}", product_id, ctx.sender);
+        self.emit_event(VeriCharmEvent::DisputeEscalated {
+            product_id,
+            reporter: ctx.sender.clone(),
+        });
+        Ok(())
+    }
+
+    /// Rule on an escalated dispute (arbitrator only). The ruling is final.
+    /// An `Authentic` ruling is archived, so later reports on the token go
+    /// through the manufacturer and escalation again.
+    fn arbitrate(&mut self, ctx: &Context, product_id: ProductId, ruling: Ruling) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        if self.arbitrator.as_ref() != Some(&ctx.sender) {
+            return Err(VeriCharmError::NotArbitrator);
+        }
+        
+        let mut dispute = self.disputes.get(&product_id)
+            .ok_or(VeriCharmError::DisputeNotFound)?;
+        if dispute.ruling.is_some() {
+            return Err(VeriCharmError::DisputeAlreadyRuled);
+        }
+        
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        match ruling {
+            Ruling::Authentic => {
+                charm_token.frozen = false;
+                self.counterfeit_reports.remove(&product_id);
+            }
+            Ruling::Counterfeit => charm_token.frozen = true,
+        }
+        self.products.insert(product_id.clone(), charm_token);
+        
+        log!("Dispute over {} ruled {:?} by arbitrator", product_id, ruling);
+        self.emit_event(VeriCharmEvent::DisputeRuled {
+            product_id: product_id.clone(),
+            ruling: ruling.clone(),
+        });
+        let authentic = ruling == Ruling::Authentic;
+        dispute.ruling = Some(ruling);
+        if authentic {
+            self.disputes.remove(&product_id);
+            let mut archived = self.archived_disputes.get(&product_id).unwrap_or_default();
+            archived.push(dispute);
+            self.archived_disputes.insert(product_id, archived);
+        } else {
+            self.disputes.insert(product_id, dispute);
+        }
+        Ok(())
+    }
+
+    /// Claim a branded product ID namespace (e.g. "NIKE-AJ1"). Can be set once.
+    fn set_id_namespace(&mut self, ctx: &Context, namespace: String) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut manufacturer = self.manufacturers.get(&ctx.sender)
+            .ok_or(VeriCharmError::UnauthorizedManufacturer)?;
+        
+        if manufacturer.id_namespace.is_some() {
+            return Err(VeriCharmError::NamespaceAlreadySet);
+        }
+        
+        let valid = !namespace.is_empty()
+            && namespace.len() <= MAX_NAMESPACE_LEN
+            && namespace.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'-');
+        if !valid {
+            return Err(VeriCharmError::InvalidNamespace);
+        }
+        
+        if self.id_namespaces.contains_key(&namespace) {
+            return Err(VeriCharmError::NamespaceTaken);
+        }
+        
+        self.id_namespaces.insert(namespace.clone(), ctx.sender.clone());
+        self.namespace_counters.insert(namespace.clone(), manufacturer.products_minted);
+        manufacturer.id_namespace = Some(namespace.clone());
+        self.manufacturers.insert(ctx.sender.clone(), manufacturer);
+        
+        log!("Manufacturer {} claimed ID namespace {}", ctx.sender, namespace);
+        Ok(())
+    }
+
+    /// Root of the last published snapshot, for air-gapped verifiers
+    fn snapshot_root(&self) -> Option<Hash> {
+        self.published_snapshot.as_ref().map(|snapshot| snapshot.root)
+    }
+
+    /// Proof of a token's verification hash, as of its scan, against `snapshot_root`
+    fn snapshot_proof(&self, product_id: ProductId) -> Result<MerkleProof, Self::Error> {
+        let snapshot = self.published_snapshot.as_ref()
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        let buffer = usize::from(snapshot.buffer);
+        self.snapshot_leaf_index[buffer].get(&product_id)
+            .and_then(|index| merkle::prove(snapshot.token_count, index, &self.snapshot_trees[buffer]))
+            .ok_or(VeriCharmError::ProductNotFound)
+    }
+
+    /// Set the snapshot publishing interval in blocks (admin only)
+    fn set_snapshot_interval(&mut self, ctx: &Context, interval: u64) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.snapshot_interval = interval;
+        Ok(())
+    }
+
+    /// Advance the snapshot scan by at most `MAX_SNAPSHOT_BATCH` tokens;
+    /// callable by anyone, and a new scan may start once per interval. Each
+    /// leaf is a token's verification hash when its page was scanned. Returns
+    /// the root once the scan completes and is published, `None` while it is
+    /// still in progress.
+    fn publish_snapshot(&mut self, ctx: &Context) -> Result<Option<Hash>, Self::Error> {
+        self.ensure_not_paused()?;
+        let mut scan = match self.pending_snapshot.take() {
+            Some(scan) => scan,
+            None => {
+                if self.snapshot_interval == 0
+                    || ctx.block_height < self.last_snapshot_block + self.snapshot_interval
+                {
+                    return Err(VeriCharmError::SnapshotTooEarly);
+                }
+                self.last_snapshot_block = ctx.block_height;
+                SnapshotScan {
+                    buffer: self.published_snapshot.as_ref().map_or(0, |published| 1 - published.buffer),
+                    ..SnapshotScan::default()
+                }
+            }
+        };
+        
+        let buffer = usize::from(scan.buffer);
+        let next = paginate_process(&self.products, scan.cursor.as_ref(), MAX_SNAPSHOT_BATCH, |product_id, token| {
+            let index = scan.accumulator.append(token.calculate_verification_hash(), &mut self.snapshot_trees[buffer]);
+            scan.id_set_hash = snapshot_id_set_hash(&scan.id_set_hash, &product_id);
+            self.snapshot_leaf_index[buffer].insert(product_id, index);
+        });
+        if let Some(cursor) = next {
+            scan.cursor = Some(cursor);
+            self.pending_snapshot = Some(scan);
+            return Ok(None);
+        }
+        
+        let root = scan.accumulator.root();
+        self.published_snapshot = Some(StateSnapshot {
+            root,
+            token_count: scan.accumulator.len(),
+            id_set_hash: scan.id_set_hash,
+            buffer: scan.buffer,
+        });
+        self.emit_event(VeriCharmEvent::SnapshotPublished {
+            root,
+            block_height: ctx.block_height,
+        });
+        Ok(Some(root))
+    }
+
+    /// Enable or disable closed-ecosystem transfer restrictions (admin only)
+    fn set_closed_ecosystem(&mut self, ctx: &Context, closed: bool) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.closed_ecosystem = closed;
+        Ok(())
+    }
+
+    /// Verify several products at once. With `fail_fast`, stop at the first
+    /// failure (an error or an inauthentic result). Proof nullifiers are
+    /// consumed as each item verifies.
+    fn verify_batch(
+        &mut self,
+        ctx: &Context,
+        items: Vec<(ProductId, VerificationData)>,
+        fail_fast: bool,
+    ) -> Result<BatchVerification, Self::Error> {
+        self.ensure_not_paused()?;
+        let mut batch = BatchVerification {
+            results: Vec::with_capacity(items.len()),
+            first_failure: None,
+        };
+        
+        for (index, (product_id, verification_data)) in items.into_iter().enumerate() {
+            let result = self.verify_and_consume(ctx, product_id, verification_data);
+            let failure = match &result {
+                Ok(verification) if verification.is_authentic => None,
+                Ok(_) => Some(VeriCharmError::NotAuthentic),
+                Err(error) => Some(error.clone()),
+            };
+            batch.results.push(result);
+            
+            if let Some(reason) = failure {
+                if batch.first_failure.is_none() {
+                    batch.first_failure = Some((index as u32, reason));
+                }
+                if fail_fast {
+                    break;
+                }
+            }
+        }
+        
+        Ok(batch)
+    }
+
+    /// Outstanding warranty liability of a manufacturer
+    fn warranty_liability(&self, manufacturer: Address) -> Liability {
+        self.warranty_liabilities.get(&manufacturer).unwrap_or_default()
+    }
+
+    /// Release the liability of a token whose warranty has lapsed; callable by anyone
+    fn release_expired_warranty(&mut self, ctx: &Context, product_id: ProductId) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        if charm_token.is_in_warranty(ctx.block_height) {
+            return Err(VeriCharmError::WarrantyActive);
+        }
+        
+        self.release_liability(&mut charm_token);
+        self.products.insert(product_id, charm_token);
+        Ok(())
+    }
+
+    /// Roll all but the last `keep_last` transfer records into the token's
+    /// history root to bound storage (owner only)
+    fn rollup_history(&mut self, ctx: &Context, product_id: ProductId, keep_last: u32) -> Result<u32, Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        if charm_token.current_owner != ctx.sender {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        
+        let archived = charm_token.rollup_history(keep_last as usize);
+        self.products.insert(product_id.clone(), charm_token);
+        
+        log!("Rolled up {} transfer records of {}", archived, product_id);
+        Ok(archived as u32)
+    }
+
+    /// Set the base mint fee and volume discount tiers (admin only)
+    fn set_mint_fee(&mut self, ctx: &Context, mint_fee: u64, fee_tiers: Vec<FeeTier>) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.mint_fee = mint_fee;
+        self.fee_tiers = fee_tiers;
+        Ok(())
+    }
+
+    /// Withdraw collected fees from the treasury (admin only)
+    fn withdraw_treasury(&mut self, ctx: &Context, amount: u64) -> Result<u64, Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.treasury = self.treasury.checked_sub(amount)
+            .ok_or(VeriCharmError::InsufficientTreasury)?;
+        
+        log!("Withdrew {} from treasury, {} remaining", amount, self.treasury);
+        Ok(self.treasury)
+    }
+
+    /// Fee the manufacturer would pay for their next mint
+    fn quote_mint_fee(&self, manufacturer: Address) -> Result<u64, Self::Error> {
+        let manufacturer = self.manufacturers.get(&manufacturer)
+            .ok_or(VeriCharmError::ManufacturerNotFound)?;
+        Ok(effective_mint_fee(self.mint_fee, &self.fee_tiers, manufacturer.products_minted))
+    }
+
+    /// Discard a token without rewards (e.g. item destroyed). Only ownership is
+    /// required; no warranty check is made and no raffle entry is issued.
+    fn renounce_token(&mut self, ctx: &Context, product_id: ProductId) -> Result<BurnReceipt, Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        if !charm_token.is_owned_by(&ctx.sender) {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        
+        if charm_token.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        
+        if charm_token.locked_beam.is_some() {
+            return Err(VeriCharmError::TokenLocked);
+        }
+
+        if charm_token.parent.is_some() {
+            return Err(VeriCharmError::TokenBundled);
+        }
+        if !charm_token.children.is_empty() {
+            return Err(VeriCharmError::BundleNotEmpty);
+        }
+        
+        charm_token.burned = true;
+        self.total_burned += 1;
+        self.release_liability(&mut charm_token);
+        self.record_retailer_burn(&charm_token.current_owner);
+        let event_tag = self.event_tag_for(&charm_token.manufacturer);
+        self.products.insert(product_id.clone(), charm_token);
+        self.cleanup_token_associations(&product_id);
+        
+        log!("Charm token {} renounced by {}", product_id, ctx.sender);
+        self.emit_event(VeriCharmEvent::Burned {
+            product_id: product_id.clone(),
+            burner: ctx.sender.clone(),
+            reason: BurnReason::Voluntary,
+            event_tag,
+        });
+        
+        let receipt = BurnReceipt {
+            product_id: product_id.clone(),
+            burner: ctx.sender.clone(),
+            burn_time: ctx.block_height,
+            reason: BurnReason::Voluntary,
+            raffle_entry: None,
+        };
+        self.burn_receipts.insert(product_id, receipt.clone());
+        Ok(receipt)
+    }
+
+    /// Set the caller's default warranty in seconds; 0 restores the 14-day default
+    fn set_default_warranty(&mut self, ctx: &Context, seconds: u64) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut manufacturer = self.manufacturers.get(&ctx.sender)
+            .ok_or(VeriCharmError::UnauthorizedManufacturer)?;
+        
+        manufacturer.default_warranty = seconds;
+        self.manufacturers.insert(ctx.sender.clone(), manufacturer);
+        
+        log!("Default warranty for {} set to {}s", ctx.sender, seconds);
+        Ok(())
+    }
+
+    /// Set or clear the tag attached to the caller's token events
+    fn set_event_tag(&mut self, ctx: &Context, event_tag: Option<String>) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut manufacturer = self.manufacturers.get(&ctx.sender)
+            .ok_or(VeriCharmError::UnauthorizedManufacturer)?;
+        
+        if let Some(tag) = &event_tag {
+            if tag.is_empty() || tag.len() > MAX_EVENT_TAG_LEN {
+                return Err(VeriCharmError::InvalidEventTag);
+            }
+        }
+        
+        manufacturer.event_tag = event_tag;
+        self.manufacturers.insert(ctx.sender.clone(), manufacturer);
+        Ok(())
+    }
+
+    /// Set the co-owner limit per token (admin only)
+    fn set_max_co_owners(&mut self, ctx: &Context, max_co_owners: u32) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.max_co_owners = max_co_owners;
+        Ok(())
+    }
+
+    /// Add a co-owner who may transfer or burn the token (owner only)
+    fn add_co_owner(&mut self, ctx: &Context, product_id: ProductId, co_owner: Address) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        if charm_token.current_owner != ctx.sender {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        self.validate_address(ctx, &co_owner)?;
+        
+        if charm_token.is_owned_by(&co_owner) {
+            return Ok(());
+        }
+        
+        let limit = match self.max_co_owners {
+            0 => DEFAULT_MAX_CO_OWNERS,
+            limit => limit,
+        };
+        if charm_token.co_owners.len() as u32 >= limit {
+            return Err(VeriCharmError::TooManyCoOwners);
+        }
+        
+        charm_token.co_owners.push(co_owner.clone());
+        self.products.insert(product_id.clone(), charm_token);
+        
+        log!("Co-owner {} added to {}", co_owner, product_id);
+        Ok(())
+    }
+
+    /// Remove a co-owner (owner only)
+    fn remove_co_owner(&mut self, ctx: &Context, product_id: ProductId, co_owner: Address) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        if charm_token.current_owner != ctx.sender {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        
+        charm_token.co_owners.retain(|address| *address != co_owner);
+        self.products.insert(product_id, charm_token);
+        Ok(())
+    }
+
+    /// Replace a token with a fresh one for the same owner, e.g. after a recall.
+    /// The old token is burned and the replacement records it in `reissued_from`.
+    fn reissue_token(&mut self, ctx: &Context, product_id: ProductId) -> Result<CharmToken, Self::Error> {
+        self.ensure_not_paused()?;
+        let mut original = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        if original.manufacturer != ctx.sender {
+            return Err(VeriCharmError::UnauthorizedManufacturer);
+        }
+        if original.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        if original.locked_beam.is_some() {
+            return Err(VeriCharmError::TokenLocked);
+        }
+
+        if original.parent.is_some() {
+            return Err(VeriCharmError::TokenBundled);
+        }
+        if !original.children.is_empty() {
+            return Err(VeriCharmError::BundleNotEmpty);
+        }
+        
+        let manufacturer = self.manufacturers.get(&ctx.sender)
+            .ok_or(VeriCharmError::UnauthorizedManufacturer)?;
+        let replacement_id = self.next_product_id(&manufacturer, &original.product_data)?;
+        
+        let mut replacement = CharmToken::new(
+            replacement_id,
+            ctx.sender.clone(),
+            original.product_data.clone(),
+            original.metadata.clone(),
+            ctx.block_height,
+            original.warranty_remaining(ctx.block_height),
+        );
+        // The replacement leaves the manufacturer straight for the owner
+        if original.current_owner != ctx.sender {
+            replacement.transfer_history.push(TransferRecord {
+                from: ctx.sender.clone(),
+                to: original.current_owner.clone(),
+                timestamp: ctx.block_height,
+                tx_hash: ctx.tx_hash.clone(),
+            });
+            replacement.current_owner = original.current_owner.clone();
+        }
+        replacement.reissued_from = Some(product_id.clone());
+        
+        // The replacement goes to the same owner, so a retailer owner's
+        // held count is unchanged: one token burned, one received
+        original.burned = true;
+        self.total_burned += 1;
+        self.release_liability(&mut original);
+        self.products.insert(product_id.clone(), original);
+        self.cleanup_token_associations(&product_id);
+        
+        self.record_mint(ctx, manufacturer, core::slice::from_mut(&mut replacement));
+        
+        log!("Charm token {} reissued as {}", product_id, replacement.id);
+        self.emit_event(VeriCharmEvent::Reissued {
+            original_id: product_id,
+            replacement_id: replacement.id.clone(),
+        });
+        Ok(replacement)
+    }
+
+    /// Settle a token beamed in from another chain (admin relayer only).
+    /// Incompatible payload versions are rejected before deserializing.
+    fn settle_incoming_beam(
+        &mut self,
+        ctx: &Context,
+        source_chain: ChainId,
+        payload: Vec<u8>,
+    ) -> Result<ProductId, Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        
+        let charm_token = decode_beam_payload(&payload)?;
+        let product_id = charm_token.id.clone();
+        if self.products.contains_key(&product_id) {
+            return Err(VeriCharmError::DuplicateProductId);
+        }
+        
+        self.index_owner(&charm_token.current_owner, &product_id);
+        self.products.insert(product_id.clone(), charm_token);
+        self.total_derived += 1;
+        
+        log!("Incoming beam of {} from {} settled", product_id, source_chain);
+        self.emit_event(VeriCharmEvent::BeamSettled {
+            product_id: product_id.clone(),
+            source_chain,
+        });
+        Ok(product_id)
+    }
+
+    /// Recall every token in a production batch (manufacturer only).
+    ///
+    /// Flags at most `MAX_RECALL_BATCH` members starting at index `start`;
+    /// returns the index to resume from, or `None` once the batch is done.
+    fn recall_batch(
+        &mut self,
+        ctx: &Context,
+        batch_id: Hash,
+        reason: String,
+        start: u32,
+    ) -> Result<Option<u32>, Self::Error> {
+        self.ensure_not_paused()?;
+        let members = self.batch_members.get(&batch_id)
+            .ok_or(VeriCharmError::BatchNotFound)?;
+        
+        let start = start as usize;
+        let end = members.len().min(start + MAX_RECALL_BATCH);
+        let mut recalled = 0;
+        for product_id in members.iter().take(end).skip(start) {
+            let Some(mut charm_token) = self.products.get(product_id) else {
+                continue;
+            };
+            if charm_token.manufacturer != ctx.sender {
+                return Err(VeriCharmError::UnauthorizedManufacturer);
+            }
+            if charm_token.burned || charm_token.recalled {
+                continue;
+            }
+            charm_token.recalled = true;
+            charm_token.recall_reason = Some(reason.clone());
+            self.products.insert(product_id.clone(), charm_token);
+            recalled += 1;
+        }
+        
+        log!("Batch recall flagged {} tokens", recalled);
+        self.emit_event(VeriCharmEvent::BatchRecalled { batch_id, reason, recalled });
+        
+        Ok((end < members.len()).then_some(end as u32))
+    }
+
+    /// Set the seconds-per-block used for new warranty periods (admin only)
+    fn set_block_time(&mut self, ctx: &Context, secs: u64) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.block_time_secs = secs;
+        log!("Block time set to {}s", secs);
+        Ok(())
+    }
+
+    /// Bring stored tokens up to the current warranty schema (admin only).
+    ///
+    /// Tokens minted before the seconds-to-blocks fix had their raw
+    /// `warranty_period` compared against block heights, so it is kept as a
+    /// block count and only the schema is stamped; their expiry block is
+    /// unchanged. Processes at most `MAX_MIGRATE_BATCH` tokens after `cursor`
+    /// and returns the cursor to resume from, or `None` when done.
+    fn migrate(
+        &mut self,
+        ctx: &Context,
+        cursor: Option<ProductId>,
+    ) -> Result<Option<ProductId>, Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        
+        let mut legacy = Vec::new();
+        let next = paginate_process(&self.products, cursor.as_ref(), MAX_MIGRATE_BATCH, |product_id, token| {
+            if token.warranty_schema < WARRANTY_SCHEMA_VERSION {
+                legacy.push((product_id, token));
+            }
+        });
+        
+        let migrated = legacy.len();
+        for (product_id, mut token) in legacy {
+            token.warranty_schema = WARRANTY_SCHEMA_VERSION;
+            self.products.insert(product_id, token);
+        }
+        
+        log!("Migrated {} tokens to warranty schema {}", migrated, WARRANTY_SCHEMA_VERSION);
+        Ok(next)
+    }
+
+    /// Register the caller as a retailer
+    fn register_retailer(&mut self, ctx: &Context, name: String, region: String) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        if self.retailers.contains_key(&ctx.sender) {
+            return Err(VeriCharmError::AlreadyRegistered);
+        }
+        
+        let retailer = Retailer::new(ctx.sender.clone(), name, region);
+        self.retailers.insert(ctx.sender.clone(), retailer);
+        
+        log!("Retailer registered: {}", ctx.sender);
+        Ok(())
+    }
+
+    /// Received, sold and held token counts for a retailer
+    fn retailer_stats(&self, address: Address) -> Result<RetailerStats, Self::Error> {
+        self.retailers.get(&address)
+            .map(|retailer| retailer.stats())
+            .ok_or(VeriCharmError::RetailerNotFound)
+    }
+
+    /// Name, verification status and mint count of a manufacturer
+    fn get_manufacturer(&self, address: Address) -> Result<ManufacturerInfo, Self::Error> {
+        self.manufacturers.get(&address)
+            .map(|manufacturer| manufacturer.info())
+            .ok_or(VeriCharmError::ManufacturerNotFound)
+    }
+
+    /// Number of registered manufacturers
+    fn manufacturer_count(&self) -> u64 {
+        self.manufacturer_count
+    }
+
+    /// Require consent for transfers to first-time holders (admin only)
+    fn set_first_contact_consent(&mut self, ctx: &Context, enabled: bool) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.first_contact_consent = enabled;
+        Ok(())
+    }
+
+    /// Accept a transfer offered to the caller
+    fn accept_transfer(&mut self, ctx: &Context, product_id: ProductId) -> Result<TransferReceipt, Self::Error> {
+        self.ensure_not_paused()?;
+        let offer = self.pending_transfers.get(&product_id)
+            .ok_or(VeriCharmError::PendingTransferNotFound)?;
+        if offer.to != ctx.sender {
+            return Err(VeriCharmError::NotPendingRecipient);
+        }
+        
+        // The token may have changed hands or been restricted since the offer
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.current_owner != offer.from {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        self.check_transfer_by(ctx, &offer.from, &charm_token, &offer.to)?;
+        
+        // An offer made under an oracle attestation completes only while the
+        // attestation is valid and unspent
+        if let Some((nullifier, valid_until)) = offer.attestation {
+            if ctx.block_height > valid_until {
+                return Err(VeriCharmError::ConditionNotMet);
+            }
+            if self.used_attestations.contains_key(&nullifier) {
+                return Err(VeriCharmError::AttestationAlreadyUsed);
+            }
+            self.used_attestations.insert(nullifier, true);
+        }
+        
+        Ok(self.complete_transfer(ctx, charm_token, offer.to, offer.zk_proof))
+    }
+
+    /// Offer a token to a buyer who must `accept_transfer` to take it, e.g. a
+    /// marketplace sale (owner only, one offer per token at a time)
+    fn initiate_transfer(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        new_owner: Address,
+        zk_proof: Option<ZkProof>,
+    ) -> Result<PendingTransfer, Self::Error> {
+        self.ensure_not_paused()?;
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        self.check_transfer(ctx, &charm_token, &new_owner)?;
+        if self.pending_transfers.contains_key(&product_id) {
+            return Err(VeriCharmError::TransferAlreadyPending);
+        }
+        
+        Ok(self.offer_transfer(ctx, &charm_token, new_owner, zk_proof))
+    }
+
+    /// Withdraw a pending offer (the offering owner only)
+    fn cancel_pending_transfer(&mut self, ctx: &Context, product_id: ProductId) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let offer = self.pending_transfers.get(&product_id)
+            .ok_or(VeriCharmError::PendingTransferNotFound)?;
+        if offer.from != ctx.sender {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        
+        self.pending_transfers.remove(&product_id);
+        log!("Transfer offer of {} to {} cancelled", product_id, offer.to);
+        Ok(())
+    }
+
+    /// Set the contract's Groth16 verifying key (admin only)
+    fn set_verifying_key(&mut self, ctx: &Context, verifying_key: Vec<u8>) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        
+        let circuit = VerificationCircuit { verifying_key };
+        circuit.validate()?;
+        self.circuit_params = circuit;
+        log!("Verifying key updated");
+        Ok(())
+    }
+
+    /// Register the caller's own verification circuit (verified manufacturers only)
+    fn set_manufacturer_circuit(&mut self, ctx: &Context, verifying_key: Vec<u8>) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let manufacturer = self.manufacturers.get(&ctx.sender)
+            .ok_or(VeriCharmError::UnauthorizedManufacturer)?;
+        if !manufacturer.verified {
+            return Err(VeriCharmError::ManufacturerNotVerified);
+        }
+        
+        let circuit = VerificationCircuit { verifying_key };
+        circuit.validate()?;
+        self.manufacturer_circuits.insert(ctx.sender.clone(), circuit);
+        log!("Manufacturer circuit set for {}", ctx.sender);
+        Ok(())
+    }
+
+    /// Choose how `verify_product` shows the caller as owner (owner only)
+    fn set_owner_privacy(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        level: PrivacyLevel,
+    ) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.current_owner != ctx.sender {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        
+        charm_token.owner_privacy = level;
+        self.products.insert(product_id, charm_token);
+        Ok(())
+    }
+
+    /// Configure beam parameters for a target chain (admin only)
+    fn set_chain_info(&mut self, ctx: &Context, chain: ChainId, info: ChainInfo) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.chain_info.insert(chain, info);
+        Ok(())
+    }
+
+    /// Complete a beam once its unlock tx is deep enough to be re-org safe (admin only)
+    fn complete_cross_chain_beam(
+        &mut self,
+        ctx: &Context,
+        beam_id: BeamId,
+        proof: UnlockProof,
+    ) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        
+        let beam = self.completable_beam(&beam_id, proof.included_at)?;
+        
+        // The including block counts as the first confirmation
+        let required = self.chain_info.get(&beam.target_chain)
+            .map_or(0, |info| info.required_confirmations);
+        let confirmations = ctx.block_height
+            .checked_sub(proof.included_at)
+            .map_or(0, |depth| depth + 1);
+        if confirmations < required {
+            return Err(VeriCharmError::InsufficientConfirmations);
+        }
+        
+        self.finish_beam(ctx, beam, proof.unlock_tx_hash);
+        
+        log!("Cross-chain beam completed with {} confirmations", confirmations);
+        Ok(())
+    }
+
+    /// Complete a beam on a chain with no confirmation requirement (admin only).
+    /// Chains that require confirmations go through `complete_cross_chain_beam`.
+    fn complete_beam(&mut self, ctx: &Context, beam_id: BeamId, unlock_tx_hash: Hash) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        
+        let beam = self.completable_beam(&beam_id, ctx.block_height)?;
+        let required = self.chain_info.get(&beam.target_chain)
+            .map_or(0, |info| info.required_confirmations);
+        if required > 0 {
+            return Err(VeriCharmError::InsufficientConfirmations);
+        }
+        
+        self.finish_beam(ctx, beam, unlock_tx_hash);
+        
+        log!("Cross-chain beam completed");
+        Ok(())
+    }
+
+    /// Set who may blacklist tokens (admin only)
+    fn set_blacklist_policy(&mut self, ctx: &Context, policy: BlacklistPolicy) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.blacklist_policy = policy;
+        Ok(())
+    }
+
+    /// Consent to the admin blacklisting one of the caller's tokens (manufacturer only)
+    fn consent_to_blacklist(&mut self, ctx: &Context, product_id: ProductId) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.manufacturer != ctx.sender {
+            return Err(VeriCharmError::UnauthorizedManufacturer);
+        }
+        
+        self.blacklist_consents.insert(product_id, true);
+        Ok(())
+    }
+
+    /// Permanently bar a token from moving, as allowed by the blacklist policy
+    fn blacklist_token(&mut self, ctx: &Context, product_id: ProductId) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        match self.blacklist_policy {
+            BlacklistPolicy::AdminOnly => self.ensure_admin(ctx)?,
+            BlacklistPolicy::AdminWithManufacturerConsent => {
+                self.ensure_admin(ctx)?;
+                if !self.blacklist_consents.contains_key(&product_id) {
+                    return Err(VeriCharmError::BlacklistConsentRequired);
+                }
+            }
+            BlacklistPolicy::ArbitratorOnly => {
+                if self.arbitrator.as_ref() != Some(&ctx.sender) {
+                    return Err(VeriCharmError::NotArbitrator);
+                }
+            }
+        }
+        
+        charm_token.blacklisted = true;
+        self.products.insert(product_id.clone(), charm_token);
+        self.blacklist_consents.remove(&product_id);
+        
+        log!("Charm token {} blacklisted", product_id);
+        self.emit_event(VeriCharmEvent::TokenBlacklisted { product_id });
+        Ok(())
+    }
+
+    /// Set the maximum `batch_mint_charms` size (admin only)
+    fn set_max_batch_mint(&mut self, ctx: &Context, max_items: u32) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.max_batch_mint = max_items;
+        Ok(())
+    }
+
+    /// Cap the total number of tokens that may ever be minted (admin only)
+    fn set_max_supply(&mut self, ctx: &Context, max_supply: Option<u64>) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.max_supply = max_supply;
+        Ok(())
+    }
+
+    /// Set the per-manufacturer mints allowed per block; 0 = unlimited (admin only)
+    fn set_mint_rate_limit(&mut self, ctx: &Context, limit: u32) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.mint_rate_limit = limit;
+        Ok(())
+    }
+
+    /// Mint a production run in one call. Every check runs before any state is
+    /// written, so either the whole batch is minted under a contiguous ID range
+    /// or nothing is.
+    #[payable]
+    fn batch_mint_charms(
+        &mut self,
+        ctx: &Context,
+        items: Vec<(ProductData, TokenMetadata)>,
+    ) -> Result<Vec<CharmToken>, Self::Error> {
+        self.ensure_not_paused()?;
+        let max_items = match self.max_batch_mint {
+            0 => DEFAULT_MAX_BATCH_MINT,
+            limit => limit,
+        };
+        if items.len() > max_items as usize {
+            return Err(VeriCharmError::BatchTooLarge);
+        }
+        for (product_data, _) in &items {
+            product_data.validate()?;
+        }
+        
+        let manufacturer = self.resolve_minter(ctx)?;
+        
+        if !manufacturer.verified {
+            return Err(VeriCharmError::ManufacturerNotVerified);
+        }
+        
+        self.check_mint_rate(ctx, &manufacturer.address, items.len())?;
+        let mut digests = BTreeSet::new();
+        for (product_data, _) in &items {
+            let digest = self.verify_product_signature(ctx, &manufacturer, product_data)?;
+            if !digests.insert(digest) {
+                return Err(VeriCharmError::ProductDataAlreadyMinted);
+            }
+        }
+        
+        let fee = effective_mint_fee(self.mint_fee, &self.fee_tiers, manufacturer.products_minted);
+        if ctx.value < fee.saturating_mul(items.len() as u64) {
+            return Err(VeriCharmError::InsufficientFee);
+        }
+        
+        let product_ids = self.allocate_product_ids(&manufacturer, items.iter().map(|(product_data, _)| product_data))?;
+        for digest in digests {
+            self.used_product_digests.insert(digest, true);
+        }
+        let warranty_period = self.warranty_period_blocks(&manufacturer);
+        let mut charm_tokens: Vec<CharmToken> = product_ids.into_iter()
+            .zip(items)
+            .map(|(product_id, (product_data, metadata))| {
+                CharmToken::new(
+                    product_id,
+                    manufacturer.address.clone(),
+                    product_data,
+                    metadata,
+                    ctx.block_height,
+                    warranty_period,
+                )
+            })
+            .collect();
+        
+        self.record_mint(ctx, manufacturer, &mut charm_tokens);
+        
+        log!("Batch minted {} tokens", charm_tokens.len());
+        Ok(charm_tokens)
+    }
+
+    /// Start warranty coverage at the point of sale (owner only). The start
+    /// may be backdated to the sale block, but not before mint or in the future.
+    fn start_warranty(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        start_block: u64,
+    ) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.current_owner != ctx.sender {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        if charm_token.warranty_start_block.is_some() {
+            return Err(VeriCharmError::WarrantyAlreadyStarted);
+        }
+        if start_block > ctx.block_height || start_block < charm_token.mint_time {
+            return Err(VeriCharmError::InvalidWarrantyStart);
+        }
+        // Unsold stock can't hold coverage in reserve indefinitely
+        let start_block = start_block.min(charm_token.mint_time.saturating_add(WARRANTY_ACTIVATION_BLOCKS));
+        
+        charm_token.warranty_start_block = Some(start_block);
+        self.products.insert(product_id.clone(), charm_token);
+        
+        log!("Warranty for {} started at block {}", product_id, start_block);
+        Ok(())
+    }
+
+    /// Check core state invariants for monitors. Visits at most
+    /// `MAX_INVARIANT_BATCH` tokens, then beam records; pass `next` back in
+    /// until it is `None`. An empty report across all pages means healthy.
+    fn check_invariants(&self, mut scan: InvariantScan) -> Result<InvariantReport, Self::Error> {
+        let mut violations = Vec::new();
+        
+        if !scan.products_done {
+            let mut active_count = scan.active_count;
+            let next = paginate_process(&self.products, scan.product_cursor.as_ref(), MAX_INVARIANT_BATCH, |product_id, token| {
+                if !token.burned {
+                    active_count += 1;
+                }
+                if !self.manufacturers.contains_key(&token.manufacturer) {
+                    violations.push(InvariantViolation::MissingManufacturer(product_id.clone()));
+                }
+                if token.burned && token.frozen {
+                    violations.push(InvariantViolation::BurnedAndFrozen(product_id));
+                }
+            });
+            scan.active_count = active_count;
+            
+            match next {
+                Some(cursor) => scan.product_cursor = Some(cursor),
+                None => {
+                    let expected = (self.total_minted + self.total_derived).saturating_sub(self.total_burned);
+                    if expected != scan.active_count {
+                        violations.push(InvariantViolation::SupplyMismatch {
+                            expected,
+                            found: scan.active_count,
+                        });
+                    }
+                    scan.products_done = true;
+                }
+            }
+            return Ok(InvariantReport { violations, next: Some(scan) });
+        }
+        
+        let next = paginate_process(&self.beam_records, scan.beam_cursor.as_ref(), MAX_INVARIANT_BATCH, |beam_id, beam| {
+            if !self.products.contains_key(&beam.product_id) {
+                violations.push(InvariantViolation::OrphanBeam(beam_id));
+            }
+        });
+        scan.beam_cursor = next;
+        
+        Ok(InvariantReport {
+            violations,
+            next: scan.beam_cursor.is_some().then_some(scan),
+        })
+    }
+
+    /// Revoke a compromised manufacturer's verification (admin only). Their
+    /// future mints fail; tokens already issued stay transferable.
+    fn revoke_manufacturer(&mut self, ctx: &Context, target: Address) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        
+        let mut manufacturer = self.manufacturers.get(&target)
+            .ok_or(VeriCharmError::ManufacturerNotFound)?;
+        manufacturer.verified = false;
+        manufacturer.revoked = true;
+        self.manufacturers.insert(target.clone(), manufacturer);
+        
+        log!("Manufacturer revoked: {}", target);
+        self.emit_event(VeriCharmEvent::ManufacturerRevoked { manufacturer: target });
+        Ok(())
+    }
+
+    /// Trust an oracle key for conditional transfers of the caller's tokens
+    /// (registered manufacturers only)
+    fn add_oracle_key(&mut self, ctx: &Context, oracle_key: PublicKey) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        if !self.manufacturers.contains_key(&ctx.sender) {
+            return Err(VeriCharmError::UnauthorizedManufacturer);
+        }
+        
+        let mut keys = self.oracle_keys.get(&ctx.sender).unwrap_or_default();
+        if !keys.contains(&oracle_key) {
+            keys.push(oracle_key);
+        }
+        self.oracle_keys.insert(ctx.sender.clone(), keys);
+        Ok(())
+    }
+
+    /// Transfer only if an oracle the manufacturer trusts attests that the
+    /// external condition holds for this product and recipient. Attestations
+    /// are signed in the manufacturer's domain, so they hold on this chain and
+    /// contract only, and each is spent once the transfer completes; an offer
+    /// it gates spends it on acceptance.
+    fn transfer_charm_conditional(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        new_owner: Address,
+        oracle_attestation: Option<OracleAttestation>,
+    ) -> Result<TransferOutcome, Self::Error> {
+        self.ensure_not_paused()?;
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        let attestation = oracle_attestation.ok_or(VeriCharmError::ConditionNotMet)?;
+        let trusted = self.oracle_keys.get(&charm_token.manufacturer).unwrap_or_default();
+        if ctx.block_height > attestation.valid_until || !trusted.contains(&attestation.oracle_key) {
+            return Err(VeriCharmError::ConditionNotMet);
+        }
+        
+        let domain = SigningDomain {
+            chain_id: ctx.chain_id.clone(),
+            contract: ctx.contract_address.clone(),
+            manufacturer: charm_token.manufacturer.clone(),
+        };
+        let payload = crypto::condition_payload(
+            &product_id,
+            &new_owner,
+            &attestation.condition,
+            attestation.valid_until,
+        );
+        let message = domain.attestation_message(&payload);
+        if !crypto::verify_signature(&attestation.oracle_key, &message, &attestation.signature) {
+            return Err(VeriCharmError::ConditionNotMet);
+        }
+        
+        let nullifier = crypto::attestation_nullifier(&product_id, &new_owner, attestation.valid_until);
+        if self.used_attestations.contains_key(&nullifier) {
+            return Err(VeriCharmError::AttestationAlreadyUsed);
+        }
+        
+        match self.transfer_charm(ctx, product_id, new_owner, None)? {
+            TransferOutcome::Completed(receipt) => {
+                self.used_attestations.insert(nullifier, true);
+                Ok(TransferOutcome::Completed(receipt))
+            }
+            TransferOutcome::Offered(mut offer) => {
+                offer.attestation = Some((nullifier, attestation.valid_until));
+                self.pending_transfers.insert(offer.product_id.clone(), offer.clone());
+                Ok(TransferOutcome::Offered(offer))
+            }
+        }
+    }
+
+    /// Confidence score from stored state alone. Without a fresh scan the tag
+    /// signal is absent, the proof stored on a token was never verified and
+    /// earns nothing, and tokens with archived history need `verify_product`
+    /// and a provenance proof to earn the provenance weight.
+    fn confidence_score(&self, product_id: ProductId) -> Result<u8, Self::Error> {
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        let manufacturer_verified = self.manufacturers.get(&charm_token.manufacturer)
+            .is_some_and(|manufacturer| manufacturer.verified);
+        
+        Ok(verification::confidence_score(&ConfidenceSignals {
+            provenance_continuous: charm_token.verify_supply_chain(),
+            zk_proof_valid: false,
+            physical_tag_verified: false,
+            manufacturer_verified,
+            counterfeit_reports: self.counterfeit_report_count(&product_id),
+        }))
+    }
+
+    /// Privileged deployer address, if initialized
+    fn admin(&self) -> Option<&Address> {
+        self.admin.as_ref()
+    }
+
+    /// Halt or resume every mutating entry point except admin handover (admin
+    /// only). Views keep working while paused.
+    fn set_paused(&mut self, ctx: &Context, paused: bool) -> Result<(), Self::Error> {
+        self.ensure_admin(ctx)?;
+        self.paused = paused;
+        log!("Contract {}", if paused { "paused" } else { "unpaused" });
+        Ok(())
+    }
+
+    /// Nominate a new admin; takes effect once they call `accept_admin` (admin only)
+    fn transfer_admin(&mut self, ctx: &Context, new_admin: Address) -> Result<(), Self::Error> {
+        self.ensure_admin(ctx)?;
+        self.validate_address(ctx, &new_admin)?;
+        if new_admin == ctx.sender {
+            return Err(VeriCharmError::AdminTransferToSelf);
+        }
+        
+        self.pending_admin = Some(new_admin.clone());
+        log!("Admin transfer to {} pending", new_admin);
+        Ok(())
+    }
+
+    /// Claim the admin role (pending admin only)
+    fn accept_admin(&mut self, ctx: &Context) -> Result<(), Self::Error> {
+        match &self.pending_admin {
+            Some(pending) if *pending == ctx.sender => {}
+            _ => return Err(VeriCharmError::Unauthorized),
+        }
+        
+        self.admin = self.pending_admin.take();
+        log!("Admin transferred to {}", ctx.sender);
+        Ok(())
+    }
+
+    /// Enable or disable ZK safe mode (admin only)
+    fn set_zk_disabled(&mut self, ctx: &Context, disabled: bool) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.zk_disabled = disabled;
+        log!("ZK verification {}", if disabled { "disabled" } else { "enabled" });
+        Ok(())
+    }
+
+    /// Mark a registered manufacturer as verified (admin only)
+    fn verify_manufacturer(&mut self, ctx: &Context, target: Address) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        
+        let mut manufacturer = self.manufacturers.get(&target)
+            .ok_or(VeriCharmError::ManufacturerNotFound)?;
+        if manufacturer.revoked {
+            return Err(VeriCharmError::ManufacturerRevoked);
+        }
+        manufacturer.verified = true;
+        self.manufacturers.insert(target.clone(), manufacturer);
+        
+        log!("Manufacturer verified: {}", target);
+        Ok(())
+    }
+
+    /// Ownership chain of a token, for wallets rendering its history
+    fn get_provenance(&self, product_id: ProductId) -> Result<Provenance, Self::Error> {
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        Ok(Provenance {
+            chain_intact: charm_token.verify_supply_chain(),
+            manufacturer: charm_token.manufacturer,
+            mint_time: charm_token.mint_time,
+            current_owner: OwnerView::render(
+                &charm_token.current_owner,
+                &charm_token.id,
+                &charm_token.owner_privacy,
+            ),
+            transfers: charm_token.transfer_history.iter()
+                .map(|record| TransferView::render(record, &charm_token.id, &charm_token.owner_privacy))
+                .collect(),
+        })
+    }
+
+    /// Set the fee per block of warranty extension (admin only)
+    fn set_warranty_extension_rate(&mut self, ctx: &Context, rate: u64) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        self.warranty_extension_rate = rate;
+        Ok(())
+    }
+
+    /// Buy extra warranty coverage (owner only). `extra_seconds` is converted
+    /// to blocks at the configured block time; returns the new expiry block.
+    #[payable]
+    fn extend_warranty(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        extra_seconds: u64,
+    ) -> Result<u64, Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.current_owner != ctx.sender {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        if charm_token.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        
+        let extra_blocks = extra_seconds / self.block_time();
+        let fee = extra_blocks.checked_mul(self.warranty_extension_rate)
+            .ok_or(VeriCharmError::WarrantyOverflow)?;
+        if ctx.value < fee {
+            return Err(VeriCharmError::InsufficientFee);
+        }
+        
+        let warranty_period = charm_token.warranty_period.checked_add(extra_blocks)
+            .ok_or(VeriCharmError::WarrantyOverflow)?;
+        let expiry = charm_token.warranty_start().checked_add(warranty_period)
+            .ok_or(VeriCharmError::WarrantyOverflow)?;
+        charm_token.warranty_period = warranty_period;
+        if charm_token.is_in_warranty(ctx.block_height) {
+            self.restore_liability(&mut charm_token);
+        }
+        self.products.insert(product_id.clone(), charm_token);
+        self.treasury = self.treasury.saturating_add(ctx.value);
+        
+        log!("Warranty for {} extended to block {}", product_id, expiry);
+        Ok(expiry)
+    }
+
+    /// Warranty coverage of a token at the current block
+    fn warranty_status(&self, ctx: &Context, product_id: ProductId) -> Result<WarrantyStatus, Self::Error> {
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        
+        Ok(WarrantyStatus {
+            active: charm_token.is_in_warranty(ctx.block_height),
+            expires_at: charm_token.warranty_expiry(),
+            remaining: charm_token.warranty_remaining(ctx.block_height),
+        })
+    }
+
+    /// Draw and remove a raffle winner (admin only). The draw seed is
+    /// `sha256(seed || block_height)`, so anyone can recompute the result
+    /// with `raffle::select_winner`.
+    fn draw_raffle(&mut self, ctx: &Context, seed: Hash) -> Result<RaffleEntry, Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        
+        let mut hasher = Sha256::new();
+        hasher.update(&seed);
+        hasher.update(&ctx.block_height.to_be_bytes());
+        let draw_seed: Hash = hasher.finalize().into();
+        
+        let entries: Vec<RaffleEntry> = self.raffle_entries.iter()
+            .map(|(_, entry)| entry)
+            .collect();
+        let index = raffle::select_winner(&entries, &draw_seed)
+            .ok_or(VeriCharmError::NoRaffleEntries)?;
+        let winner = entries[index].clone();
+        self.raffle_entries.remove(&winner.entry_id);
+        self.open_raffle_entries -= 1;
+        
+        log!("Raffle won by {} with {}", winner.participant, winner.product_id);
+        Ok(winner)
+    }
+
+    /// Number of raffle entries awaiting a draw
+    fn raffle_entry_count(&self) -> u64 {
+        self.open_raffle_entries
+    }
+
+    /// Live tokens held by `owner`
+    fn tokens_by_owner(&self, owner: Address) -> Vec<ProductId> {
+        self.owner_index.get(&owner).unwrap_or_default()
+    }
+
+    /// Cancel a timed-out beam (original sender only)
+    fn cancel_beam(&mut self, ctx: &Context, beam_id: BeamId) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut beam = self.beam_records.get(&beam_id)
+            .ok_or(VeriCharmError::BeamNotFound)?;
+        if beam.sender != ctx.sender {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        if beam.status != BeamStatus::Initiated {
+            return Err(VeriCharmError::InvalidBeamState);
+        }
+        if ctx.block_height <= beam.timeout_height {
+            return Err(VeriCharmError::BeamNotExpired);
+        }
+        
+        if let Some(mut charm_token) = self.products.get(&beam.product_id) {
+            if charm_token.locked_beam == Some(beam_id) {
+                charm_token.locked_beam = None;
+                self.products.insert(beam.product_id.clone(), charm_token);
+            }
+        }
+        
+        beam.status = BeamStatus::Cancelled;
+        let product_id = beam.product_id.clone();
+        self.beam_records.insert(beam_id, beam);
+        
+        log!("Cross-chain beam cancelled by {}", ctx.sender);
+        self.emit_event(VeriCharmEvent::BeamCancelled { beam_id, product_id });
+        Ok(())
+    }
+
+    /// Replace a token's metadata (original manufacturer only). The previous
+    /// version is kept in `metadata_history`.
+    fn update_metadata(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        metadata: TokenMetadata,
+    ) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.manufacturer != ctx.sender {
+            return Err(VeriCharmError::UnauthorizedManufacturer);
+        }
+        if charm_token.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        
+        let previous = core::mem::replace(&mut charm_token.metadata, metadata);
+        charm_token.metadata_history.push((ctx.block_height, previous));
+        self.products.insert(product_id.clone(), charm_token);
+        
+        log!("Metadata updated for {}", product_id);
+        Ok(())
+    }
+
+    /// Recall a single product (manufacturer only). Recalled tokens cannot be
+    /// transferred but can always be burned as a `ProductReturn`.
+    fn recall_product(&mut self, ctx: &Context, product_id: ProductId, reason: String) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.manufacturer != ctx.sender {
+            return Err(VeriCharmError::UnauthorizedManufacturer);
+        }
+        if charm_token.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        
+        charm_token.recalled = true;
+        charm_token.recall_reason = Some(reason.clone());
+        self.products.insert(product_id.clone(), charm_token);
+        
+        log!("Charm token {} recalled", product_id);
+        self.emit_event(VeriCharmEvent::Recalled { product_id, reason });
+        Ok(())
+    }
+
+    /// Minted products in mint order, starting at mint position `start`
+    fn list_products(&self, start: u64, limit: u32) -> Result<ProductPage, Self::Error> {
+        if limit > MAX_PRODUCT_PAGE {
+            return Err(VeriCharmError::PageTooLarge);
+        }
+        
+        let minted = self.mint_accumulator.len();
+        let end = minted.min(start.saturating_add(limit as u64));
+        let product_ids = (start..end)
+            .filter_map(|position| self.mint_sequence.get(&position))
+            .collect();
+        
+        Ok(ProductPage {
+            product_ids,
+            next_cursor: (end < minted).then_some(end),
+        })
+    }
+
+    /// Authorize `operator` to mint on the caller's behalf (verified manufacturers only)
+    fn add_operator(&mut self, ctx: &Context, operator: Address) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let manufacturer = self.manufacturers.get(&ctx.sender)
+            .ok_or(VeriCharmError::UnauthorizedManufacturer)?;
+        if !manufacturer.verified {
+            return Err(VeriCharmError::ManufacturerNotVerified);
+        }
+        self.validate_address(ctx, &operator)?;
+        
+        // An operator mints for exactly one manufacturer
+        if self.manufacturers.contains_key(&operator) {
+            return Err(VeriCharmError::NotAuthorizedOperator);
+        }
+        if self.operators.get(&operator).is_some_and(|delegator| delegator != ctx.sender) {
+            return Err(VeriCharmError::NotAuthorizedOperator);
+        }
+        
+        self.operators.insert(operator.clone(), ctx.sender.clone());
+        log!("Operator {} added for {}", operator, ctx.sender);
+        Ok(())
+    }
+
+    /// Revoke one of the caller's minting operators
+    fn remove_operator(&mut self, ctx: &Context, operator: Address) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        if self.operators.get(&operator).as_ref() != Some(&ctx.sender) {
+            return Err(VeriCharmError::NotAuthorizedOperator);
+        }
+        
+        self.operators.remove(&operator);
+        log!("Operator {} removed for {}", operator, ctx.sender);
+        Ok(())
+    }
+
+    /// Mint a parent token grouping `child_ids`, e.g. a gift set (manufacturer
+    /// or operator, owning every child). Children move with the parent until
+    /// `unbundle`. The parent is charged like any other mint.
+    #[payable]
+    fn bundle(
+        &mut self,
+        ctx: &Context,
+        child_ids: Vec<ProductId>,
+        product_data: ProductData,
+        metadata: TokenMetadata,
+    ) -> Result<CharmToken, Self::Error> {
+        self.ensure_not_paused()?;
+        product_data.validate()?;
+        let manufacturer = self.resolve_minter(ctx)?;
+        if !manufacturer.verified {
+            return Err(VeriCharmError::ManufacturerNotVerified);
+        }
+        if child_ids.is_empty() {
+            return Err(VeriCharmError::EmptyBundle);
+        }
+        if child_ids.len() > MAX_BATCH_TRANSFER {
+            return Err(VeriCharmError::BatchTooLarge);
+        }
+        self.check_mint_rate(ctx, &manufacturer.address, 1)?;
+        
+        let fee = effective_mint_fee(self.mint_fee, &self.fee_tiers, manufacturer.products_minted);
+        if ctx.value < fee {
+            return Err(VeriCharmError::InsufficientFee);
+        }
+        
+        let mut seen = BTreeSet::new();
+        let mut children = Vec::with_capacity(child_ids.len());
+        for child_id in &child_ids {
+            if !seen.insert(child_id.clone()) {
+                return Err(VeriCharmError::DuplicateBatchItem);
+            }
+            let child = self.products.get(child_id)
+                .ok_or(VeriCharmError::ProductNotFound)?;
+            if child.current_owner != ctx.sender {
+                return Err(VeriCharmError::NotTokenOwner);
+            }
+            if child.burned {
+                return Err(VeriCharmError::TokenBurned);
+            }
+            if child.frozen {
+                return Err(VeriCharmError::TokenFrozen);
+            }
+            if child.blacklisted {
+                return Err(VeriCharmError::TokenBlacklisted);
+            }
+            if child.locked_beam.is_some() {
+                return Err(VeriCharmError::TokenLocked);
+            }
+            if child.recalled {
+                return Err(VeriCharmError::ProductRecalled);
+            }
+            if child.parent.is_some() {
+                return Err(VeriCharmError::TokenBundled);
+            }
+            children.push(child);
+        }
+        
+        let digest = self.verify_product_signature(ctx, &manufacturer, &product_data)?;
+        let product_id = self.next_product_id(&manufacturer, &product_data)?;
+        self.used_product_digests.insert(digest, true);
+        let mut parent = CharmToken::new(
+            product_id,
+            manufacturer.address.clone(),
+            product_data,
+            metadata,
+            ctx.block_height,
+            self.warranty_period_blocks(&manufacturer),
+        );
+        // An operator bundling for its manufacturer takes custody of the parent
+        if ctx.sender != manufacturer.address {
+            parent.transfer_history.push(TransferRecord {
+                from: manufacturer.address.clone(),
+                to: ctx.sender.clone(),
+                timestamp: ctx.block_height,
+                tx_hash: ctx.tx_hash.clone(),
+            });
+            parent.current_owner = ctx.sender.clone();
+        }
+        parent.children = child_ids;
+        
+        for mut child in children {
+            child.parent = Some(parent.id.clone());
+            self.products.insert(child.id.clone(), child);
+        }
+        self.record_mint(ctx, manufacturer, core::slice::from_mut(&mut parent));
+        
+        log!("Bundled {} tokens into {}", parent.children.len(), parent.id);
+        Ok(parent)
+    }
+
+    /// Release a bundle's children to the caller and burn the parent (parent
+    /// owner only)
+    fn unbundle(&mut self, ctx: &Context, product_id: ProductId) -> Result<Vec<ProductId>, Self::Error> {
+        self.ensure_not_paused()?;
+        let mut parent = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if parent.current_owner != ctx.sender {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        if parent.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        if parent.locked_beam.is_some() {
+            return Err(VeriCharmError::TokenLocked);
+        }
+        if parent.children.is_empty() {
+            return Err(VeriCharmError::EmptyBundle);
+        }
+        
+        let child_ids = core::mem::take(&mut parent.children);
+        parent.burned = true;
+        self.total_burned += 1;
+        self.release_liability(&mut parent);
+        self.record_retailer_burn(&parent.current_owner);
+        self.products.insert(product_id.clone(), parent);
+        self.cleanup_token_associations(&product_id);
+        
+        for child_id in &child_ids {
+            let Some(mut child) = self.products.get(child_id) else {
+                continue;
+            };
+            child.parent = None;
+            if child.current_owner == ctx.sender {
+                self.products.insert(child_id.clone(), child);
+            } else {
+                self.complete_transfer(ctx, child, ctx.sender.clone(), None);
+            }
+        }
+        
+        log!("Unbundled {} tokens from {}", child_ids.len(), product_id);
+        Ok(child_ids)
+    }
+
+    /// Whether `serial` matches the printed serial of `product_id`. Case,
+    /// hyphens and I/L/O look-alikes are ignored.
+    fn verify_serial(&self, product_id: ProductId, serial: String) -> bool {
+        self.products.get(&product_id).is_some_and(|charm_token| {
+            !charm_token.serial.is_empty() && normalize_serial(&serial) == charm_token.serial
+        })
+    }
+
+    /// Challenge for proving ownership of `product_id` behind `commitment`
+    fn prove_ownership(&self, product_id: ProductId, commitment: Hash) -> Result<OwnershipChallenge, Self::Error> {
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        
+        let challenge = verification::ownership_challenge(
+            &product_id,
+            &commitment,
+            &charm_token.calculate_verification_hash(),
+        );
+        Ok(OwnershipChallenge { product_id, commitment, challenge })
+    }
+
+    /// Check a ZK proof that the prover behind `challenge.commitment` is the
+    /// token's current owner. Public inputs are the challenge, the commitment
+    /// and the owner digest, each reduced into the scalar field.
+    fn verify_ownership_proof(
+        &self,
+        challenge: OwnershipChallenge,
+        proof: ZkProof,
+    ) -> Result<bool, Self::Error> {
+        let charm_token = self.products.get(&challenge.product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        
+        // Challenges issued before the last transfer no longer match
+        let expected = verification::ownership_challenge(
+            &challenge.product_id,
+            &challenge.commitment,
+            &charm_token.calculate_verification_hash(),
+        );
+        if expected != challenge.challenge {
+            return Ok(false);
+        }
+        
+        self.circuit_params.verify_proof(&proof, &[
+            verification::hash_to_field(&challenge.challenge),
+            verification::hash_to_field(&challenge.commitment),
+            verification::hash_to_field(&verification::owner_digest(&charm_token.current_owner)),
+        ])
+    }
+
+    /// File a warranty claim for repair or replacement without burning the
+    /// token (owner only, while in warranty)
+    fn file_warranty_claim(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        description: String,
+    ) -> Result<ClaimId, Self::Error> {
+        self.ensure_not_paused()?;
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if !charm_token.is_owned_by(&ctx.sender) {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        if charm_token.burned {
+            return Err(VeriCharmError::TokenBurned);
+        }
+        if !charm_token.is_in_warranty(ctx.block_height) {
+            return Err(VeriCharmError::WarrantyExpired);
+        }
+        
+        let claim_id = self.next_claim_id;
+        self.next_claim_id += 1;
+        self.claims.insert(claim_id, WarrantyClaim {
+            product_id: product_id.clone(),
+            claimant: ctx.sender.clone(),
+            description,
+            filed_at: ctx.block_height,
+            resolution: None,
+        });
+        
+        log!("Warranty claim {} filed for {}", claim_id, product_id);
+        Ok(claim_id)
+    }
+
+    /// Close a warranty claim with a resolution note (token manufacturer only)
+    fn resolve_claim(&mut self, ctx: &Context, claim_id: ClaimId, resolution: String) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut claim = self.claims.get(&claim_id)
+            .ok_or(VeriCharmError::ClaimNotFound)?;
+        let charm_token = self.products.get(&claim.product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.manufacturer != ctx.sender {
+            return Err(VeriCharmError::UnauthorizedManufacturer);
+        }
+        if claim.resolution.is_some() {
+            return Err(VeriCharmError::ClaimAlreadyResolved);
+        }
+        
+        claim.resolution = Some((ctx.block_height, resolution));
+        self.claims.insert(claim_id, claim);
+        
+        log!("Warranty claim {} resolved", claim_id);
+        Ok(())
+    }
+
+    /// Receipt of a burned token. Tokens retired by `reissue_token` or
+    /// `unbundle` have no receipt.
+    fn get_burn_receipt(&self, product_id: ProductId) -> Result<BurnReceipt, Self::Error> {
+        let charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if !charm_token.burned {
+            return Err(VeriCharmError::NotBurned);
+        }
+        
+        self.burn_receipts.get(&product_id)
+            .ok_or(VeriCharmError::BurnReceiptNotFound)
+    }
+
+    /// Anchor the authorized distribution route of a token, e.g. distributor
+    /// then retailer (token manufacturer only). Every hop must be a registered
+    /// manufacturer or retailer.
+    fn set_expected_route(
+        &mut self,
+        ctx: &Context,
+        product_id: ProductId,
+        expected_roles: Vec<Address>,
+    ) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        let mut charm_token = self.products.get(&product_id)
+            .ok_or(VeriCharmError::ProductNotFound)?;
+        if charm_token.manufacturer != ctx.sender {
+            return Err(VeriCharmError::UnauthorizedManufacturer);
+        }
+        
+        let registered = |address: &Address| {
+            self.manufacturers.contains_key(address) || self.retailers.contains_key(address)
+        };
+        if !expected_roles.iter().all(registered) {
+            return Err(VeriCharmError::InvalidRoute);
+        }
+        
+        charm_token.expected_roles = expected_roles;
+        self.products.insert(product_id.clone(), charm_token);
+        
+        log!("Expected route set for {}", product_id);
+        Ok(())
+    }
+
+    /// Set the burn-reason circuit's Groth16 verifying key (admin only)
+    fn set_burn_reason_key(&mut self, ctx: &Context, verifying_key: Vec<u8>) -> Result<(), Self::Error> {
+        self.ensure_not_paused()?;
+        self.ensure_admin(ctx)?;
+        
+        let circuit = VerificationCircuit { verifying_key };
+        circuit.validate()?;
+        self.burn_reason_circuit = circuit;
+        log!("Burn-reason verifying key updated");
+        Ok(())
+    }
+
+    /// Commit to the reason of the caller's burn so it can later be proven
+    /// with `verify_burn_reason_proof` without naming the token (burner only,
+    /// once per burn). `blind` is `burn_reason::burn_blind` of a secret the
+    /// burner keeps. Returns the recorded commitment.
+    fn commit_burn_reason(&mut self, ctx: &Context, product_id: ProductId, blind: Field) -> Result<Field, Self::Error> {
+        self.ensure_not_paused()?;
+        let receipt = self.burn_receipts.get(&product_id)
+            .ok_or(VeriCharmError::BurnReceiptNotFound)?;
+        if receipt.burner != ctx.sender {
+            return Err(VeriCharmError::NotTokenOwner);
+        }
+        if self.committed_burns.contains_key(&product_id) {
+            return Err(VeriCharmError::BurnAlreadyCommitted);
+        }
+        
+        let commitment = burn_reason::burn_commitment(&product_id, &receipt.reason, &blind)
+            .ok_or(VeriCharmError::InvalidCommitment)?;
+        self.committed_burns.insert(product_id, true);
+        self.burn_commitments.insert(commitment, true);
+        Ok(commitment)
+    }
+
+    /// Whether `proof` shows that a recorded burn commitment opens to
+    /// `reason`. The token behind the commitment is not revealed.
+    fn verify_burn_reason_proof(&self, proof: ZkProof, commitment: Field, reason: BurnReason) -> Result<bool, Self::Error> {
+        if !self.burn_commitments.contains_key(&commitment) {
+            return Ok(false);
+        }
+        self.burn_reason_circuit.verify_burn_reason_proof(&proof, &commitment, &reason)
+    }
+}
+
+// Entry point for WASM compilation
+#[no_mangle]
+pub extern "C" fn _start() {
+    contract::run(VeriCharmContract::<HostStorage>::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn mint_transfer_burn_flow_runs_on_memory_storage() {
+        let mut contract = contract();
+        let product_id = mint(&mut contract, "SN-1", 10);
+        assert!(contract.exists(product_id.clone()));
+        assert_eq!(contract.tokens_by_owner(address(ADMIN)), vec![product_id.clone()]);
+
+        let outcome = contract.transfer_charm(&context(ADMIN, 11), product_id.clone(), address(2), None).unwrap();
+        assert!(matches!(outcome, TransferOutcome::Completed(_)));
+        assert!(contract.tokens_by_owner(address(ADMIN)).is_empty());
+        assert_eq!(contract.tokens_by_owner(address(2)), vec![product_id.clone()]);
+
+        let after_warranty = 10 + contract.get_charm(&context(2, 11), product_id.clone()).unwrap().warranty_period;
+        assert_eq!(
+            contract.burn_charm(&context(3, after_warranty), product_id.clone(), BurnReason::RaffleEntry).unwrap_err(),
+            VeriCharmError::NotTokenOwner,
+        );
+        let receipt = contract.burn_charm(&context(2, after_warranty), product_id.clone(), BurnReason::RaffleEntry).unwrap();
+        assert_eq!(receipt.burner, address(2));
+        assert!(contract.get_charm(&context(2, 11), product_id.clone()).unwrap().burned);
+        assert_eq!(contract.raffle_entry_count(), 1);
+        assert_eq!(
+            contract.transfer_charm(&context(2, after_warranty + 1), product_id, address(4), None).unwrap_err(),
+            VeriCharmError::TokenBurned,
+        );
+    }
+
+    #[test]
+    fn recompute_repairs_a_corrupted_mint_counter() {
+        let mut contract = contract();
+        for serial_number in ["SN-1", "SN-2", "SN-3"] {
+            mint(&mut contract, serial_number, 10);
+        }
+        let mut record = contract.manufacturers.get(&address(ADMIN)).unwrap();
+        record.products_minted = 99;
+        contract.manufacturers.insert(address(ADMIN), record);
+
+        assert_eq!(
+            contract.recompute_manufacturer_stats(&context(2, 11), address(ADMIN)),
+            Err(VeriCharmError::Unauthorized),
+        );
+        assert_eq!(contract.recompute_manufacturer_stats(&context(ADMIN, 11), address(ADMIN)), Ok(true));
+        assert_eq!(contract.get_manufacturer(address(ADMIN)).unwrap().products_minted, 3);
+    }
+
+    #[test]
+    fn private_owners_are_hidden_from_provenance_and_get_charm() {
+        let mut contract = contract();
+        let product_id = mint(&mut contract, "SN-1", 10);
+        contract.transfer_charm(&context(ADMIN, 11), product_id.clone(), address(2), None).unwrap();
+
+        contract.set_owner_privacy(&context(2, 12), product_id.clone(), PrivacyLevel::PseudonymousHash).unwrap();
+        let provenance = contract.get_provenance(product_id.clone()).unwrap();
+        assert!(provenance.chain_intact);
+        let last = provenance.transfers.last().unwrap();
+        assert_eq!(last.to, provenance.current_owner);
+        assert_ne!(last.to, OwnerView::Public(address(2)));
+        assert_eq!(last.tx_hash, None);
+
+        contract.set_owner_privacy(&context(2, 12), product_id.clone(), PrivacyLevel::Private).unwrap();
+        let provenance = contract.get_provenance(product_id.clone()).unwrap();
+        assert!(provenance.transfers.iter().all(|view| view.from == OwnerView::Private && view.to == OwnerView::Private));
+
+        let stranger_view = contract.get_charm(&context(3, 12), product_id.clone()).unwrap();
+        assert_eq!(stranger_view.current_owner, Address::default());
+        assert!(stranger_view.transfer_history.is_empty());
+        let owner_view = contract.get_charm(&context(2, 12), product_id).unwrap();
+        assert_eq!(owner_view.current_owner, address(2));
+        assert_eq!(owner_view.transfer_history.len(), 2);
+    }
+
+    #[test]
+    fn init_runs_once_and_never_on_populated_state() {
+        let mut contract = contract();
+        assert_eq!(contract.admin(), Some(&address(ADMIN)));
+        assert_eq!(contract.init(&context(2, 5)), Err(VeriCharmError::AlreadyInitialized));
+
+        // State saved before `admin` existed deserializes without one
+        let mut legacy = contract;
+        legacy.admin = None;
+        assert_eq!(legacy.init(&context(2, 5)), Err(VeriCharmError::AlreadyInitialized));
+        assert_eq!(legacy.admin(), None);
+    }
+
+    #[test]
+    fn splits_get_their_own_identity_and_skip_bundles() {
+        let mut contract = contract();
+        let bulk = mint_data(&mut contract, ProductData { quantity: Some(10), ..product_data(ADMIN, "SN-1") }, 10);
+        let split = contract.transfer_quantity(&context(ADMIN, 11), bulk.clone(), address(2), 4).unwrap();
+        let parent = contract.get_charm(&context(ADMIN, 11), bulk.clone()).unwrap();
+        assert_eq!(split.quantity, Some(4));
+        assert_eq!(parent.quantity, Some(6));
+        assert_ne!(split.serial, parent.serial);
+        assert!(contract.verify_serial(split.id.clone(), split.serial.clone()));
+        assert!(!contract.verify_serial(split.id, parent.serial));
+
+        let bundle_data = sign_product(ADMIN, ProductData { quantity: Some(5), ..product_data(ADMIN, "SN-BUNDLE") });
+        let bundle = contract.bundle(&context(ADMIN, 12), vec![bulk.clone()], bundle_data, metadata()).unwrap();
+        assert_eq!(
+            contract.transfer_quantity(&context(ADMIN, 13), bundle.id, address(2), 1).unwrap_err(),
+            VeriCharmError::BundleNotEmpty,
+        );
+        assert_eq!(
+            contract.transfer_quantity(&context(ADMIN, 13), bulk, address(2), 1).unwrap_err(),
+            VeriCharmError::TokenBundled,
+        );
+    }
+
+    #[test]
+    fn batch_recall_reaches_split_tokens() {
+        let mut contract = contract();
+        let bulk = mint_data(&mut contract, ProductData { quantity: Some(10), ..product_data(ADMIN, "SN-1") }, 10);
+        let split = contract.transfer_quantity(&context(ADMIN, 11), bulk.clone(), address(2), 4).unwrap();
+        let batch_id = split.batch_id.unwrap();
+
+        assert_eq!(contract.recall_batch(&context(ADMIN, 12), batch_id, "contamination".into(), 0), Ok(None));
+        assert!(contract.get_charm(&context(ADMIN, 12), bulk).unwrap().recalled);
+        assert!(contract.get_charm(&context(2, 12), split.id).unwrap().recalled);
+    }
+
+    #[test]
+    fn mint_inclusion_proofs_verify_against_the_mint_root() {
+        let mut contract = contract();
+        let ids: Vec<ProductId> = ["SN-1", "SN-2", "SN-3"].iter().map(|serial| mint(&mut contract, serial, 10)).collect();
+        let root = contract.mint_root();
+        for product_id in ids {
+            let proof = contract.mint_inclusion_proof(product_id).unwrap();
+            assert_eq!(proof.siblings.len(), merkle::TREE_DEPTH);
+            assert!(proof.verify(&root));
+        }
+        assert_eq!(contract.mint_inclusion_proof("missing".into()).unwrap_err(), VeriCharmError::ProductNotFound);
+    }
+
+    #[test]
+    fn snapshots_publish_root_count_and_id_set_hash() {
+        let mut contract = contract();
+        contract.set_snapshot_interval(&context(ADMIN, 1), 10).unwrap();
+        let first = mint(&mut contract, "SN-1", 10);
+        mint(&mut contract, "SN-2", 10);
+        let root = contract.publish_snapshot(&context(2, 20)).unwrap().unwrap();
+        assert_eq!(contract.snapshot_root(), Some(root));
+
+        let published = contract.published_snapshot.clone().unwrap();
+        assert_eq!(published.token_count, 2);
+        let mut ids: Vec<ProductId> = contract.products.iter().map(|(product_id, _)| product_id).collect();
+        ids.sort();
+        let expected = ids.iter().fold([0u8; 32], |hash, product_id| snapshot_id_set_hash(&hash, product_id));
+        assert_eq!(published.id_set_hash, expected);
+
+        let proof = contract.snapshot_proof(first.clone()).unwrap();
+        assert!(proof.verify(&root));
+
+        // The next scan builds in the other buffer
+        mint(&mut contract, "SN-3", 25);
+        let next_root = contract.publish_snapshot(&context(2, 30)).unwrap().unwrap();
+        assert_ne!(next_root, root);
+        assert!(contract.snapshot_proof(first).unwrap().verify(&next_root));
+        let published = contract.published_snapshot.as_ref().unwrap();
+        assert_eq!((published.token_count, published.buffer), (3, 1));
+    }
+
+    #[test]
+    fn owner_bundle_lists_only_the_callers_live_tokens() {
+        let mut contract = contract();
+        let kept = mint(&mut contract, "SN-1", 10);
+        let sold = mint(&mut contract, "SN-2", 10);
+        contract.transfer_charm(&context(ADMIN, 11), sold.clone(), address(2), None).unwrap();
+
+        let bundle = contract.export_owner_bundle(&context(ADMIN, 12));
+        assert_eq!(bundle.entries.iter().map(|(product_id, _)| product_id.clone()).collect::<Vec<_>>(), vec![kept]);
+        assert!(bundle.is_intact());
+        let bundle = contract.export_owner_bundle(&context(2, 12));
+        assert_eq!(bundle.entries.len(), 1);
+        assert_eq!(bundle.entries[0].0, sold);
+        assert!(contract.export_owner_bundle(&context(3, 12)).entries.is_empty());
+    }
+
+    #[test]
+    fn warranty_start_cannot_be_future_or_past_the_activation_window() {
+        let mut contract = contract();
+        let product_id = mint(&mut contract, "SN-1", 10);
+        assert_eq!(
+            contract.start_warranty(&context(ADMIN, 20), product_id.clone(), 21),
+            Err(VeriCharmError::InvalidWarrantyStart),
+        );
+
+        let late = 10 + WARRANTY_ACTIVATION_BLOCKS + 5_000;
+        contract.transfer_charm(&context(ADMIN, late), product_id.clone(), address(2), None).unwrap();
+        contract.start_warranty(&context(2, late), product_id.clone(), late).unwrap();
+        let token = contract.get_charm(&context(2, late), product_id).unwrap();
+        assert_eq!(token.warranty_start_block, Some(10 + WARRANTY_ACTIVATION_BLOCKS));
+    }
+
+    #[test]
+    fn reissued_tokens_keep_an_intact_chain_and_their_lineage() {
+        let mut contract = contract();
+        let original = mint(&mut contract, "SN-1", 10);
+        contract.transfer_charm(&context(ADMIN, 11), original.clone(), address(2), None).unwrap();
+        contract.recall_product(&context(ADMIN, 12), original.clone(), "faulty battery".into()).unwrap();
+
+        let replacement = contract.reissue_token(&context(ADMIN, 13), original.clone()).unwrap();
+        assert_eq!(replacement.current_owner, address(2));
+        let provenance = contract.get_provenance(replacement.id.clone()).unwrap();
+        assert!(provenance.chain_intact);
+        assert_eq!(provenance.transfers.last().unwrap().to, OwnerView::Public(address(2)));
+
+        let result = contract
+            .verify_product(&context(3, 14), replacement.id.clone(), verification_data(&replacement))
+            .unwrap();
+        assert!(result.is_authentic);
+        assert_eq!(result.recall_lineage, vec![original]);
+    }
+
+    #[test]
+    fn operator_bundles_record_the_custody_hand_off() {
+        let mut contract = contract();
+        let operator = 3;
+        contract.add_operator(&context(ADMIN, 10), address(operator)).unwrap();
+        let child = mint(&mut contract, "SN-1", 10);
+        contract.transfer_charm(&context(ADMIN, 11), child.clone(), address(operator), None).unwrap();
+
+        let bundle_data = product_data(ADMIN, "SN-BUNDLE");
+        let parent = contract.bundle(&context(operator, 12), vec![child], bundle_data, metadata()).unwrap();
+        assert_eq!(parent.current_owner, address(operator));
+        assert!(contract.get_provenance(parent.id).unwrap().chain_intact);
+    }
+
+    #[test]
+    fn oracle_attestations_are_spent_when_the_transfer_completes() {
+        let mut contract = contract();
+        let oracle = signing_key(9);
+        contract.add_oracle_key(&context(ADMIN, 10), public_key(&oracle)).unwrap();
+        let product_id = mint(&mut contract, "SN-1", 10);
+        let attest = |key: &k256::schnorr::SigningKey, new_owner: u8, valid_until: u64| {
+            let payload = crypto::condition_payload(&product_id, &address(new_owner), "export-license", valid_until);
+            OracleAttestation {
+                condition: "export-license".into(),
+                valid_until,
+                oracle_key: public_key(&oracle),
+                signature: sign(key, &domain(ADMIN).attestation_message(&payload)),
+            }
+        };
+
+        let forged = attest(&signing_key(8), 2, 100);
+        assert_eq!(
+            contract.transfer_charm_conditional(&context(ADMIN, 11), product_id.clone(), address(2), Some(forged)).unwrap_err(),
+            VeriCharmError::ConditionNotMet,
+        );
+
+        // Held as an offer, the attestation stays unspent until accepted
+        contract.set_first_contact_consent(&context(ADMIN, 11), true).unwrap();
+        let attestation = attest(&oracle, 2, 100);
+        let nullifier = crypto::attestation_nullifier(&product_id, &address(2), 100);
+        let outcome = contract
+            .transfer_charm_conditional(&context(ADMIN, 11), product_id.clone(), address(2), Some(attestation.clone()))
+            .unwrap();
+        assert!(matches!(outcome, TransferOutcome::Offered(ref offer) if offer.attestation == Some((nullifier, 100))));
+        assert!(!contract.used_attestations.contains_key(&nullifier));
+        contract.accept_transfer(&context(2, 12), product_id.clone()).unwrap();
+        assert!(contract.used_attestations.contains_key(&nullifier));
+
+        contract.transfer_charm(&context(2, 13), product_id.clone(), address(ADMIN), None).unwrap();
+        assert_eq!(
+            contract.transfer_charm_conditional(&context(ADMIN, 14), product_id.clone(), address(2), Some(attestation)).unwrap_err(),
+            VeriCharmError::AttestationAlreadyUsed,
+        );
+        let outcome = contract
+            .transfer_charm_conditional(&context(ADMIN, 14), product_id.clone(), address(2), Some(attest(&oracle, 2, 101)))
+            .unwrap();
+        assert!(matches!(outcome, TransferOutcome::Completed(_)));
+    }
+
+    #[test]
+    fn revoked_and_unverified_manufacturers_fail_differently() {
+        let mut contract = contract();
+        let product_id = mint(&mut contract, "SN-1", 10);
+        let token = contract.get_charm(&context(ADMIN, 10), product_id.clone()).unwrap();
+        let verify = |contract: &TestContract| {
+            contract.verify_product(&context(3, 11), product_id.clone(), verification_data(&token)).unwrap()
+        };
+        assert_eq!(verify(&contract).authenticity_reason, None);
+
+        // Registered but not verified, e.g. a token's manufacturer awaiting review
+        let mut manufacturer = contract.manufacturers.get(&address(ADMIN)).unwrap();
+        manufacturer.verified = false;
+        contract.manufacturers.insert(address(ADMIN), manufacturer);
+        let result = verify(&contract);
+        assert!(!result.is_authentic);
+        assert_eq!(result.authenticity_reason, Some(AuthenticityFailure::ManufacturerUnverified));
+
+        contract.revoke_manufacturer(&context(ADMIN, 11), address(ADMIN)).unwrap();
+        assert_eq!(verify(&contract).authenticity_reason, Some(AuthenticityFailure::ManufacturerRevoked));
+    }
+
+    #[test]
+    fn liability_follows_expiry_burns_and_extensions() {
+        let mut contract = contract();
+        let covered = mint_data(&mut contract, ProductData { declared_value: 500, ..product_data(ADMIN, "SN-1") }, 10);
+        let burned = mint_data(&mut contract, ProductData { declared_value: 300, ..product_data(ADMIN, "SN-2") }, 10);
+        let liability = |contract: &TestContract| {
+            let liability = contract.warranty_liability(address(ADMIN));
+            (liability.token_count, liability.total_value)
+        };
+        assert_eq!(liability(&contract), (2, 800));
+
+        let expired = 10 + contract.get_charm(&context(ADMIN, 10), covered.clone()).unwrap().warranty_period;
+        assert_eq!(
+            contract.release_expired_warranty(&context(3, expired - 1), covered.clone()),
+            Err(VeriCharmError::WarrantyActive),
+        );
+        contract.release_expired_warranty(&context(3, expired), covered.clone()).unwrap();
+        assert_eq!(liability(&contract), (1, 300));
+        contract.burn_charm(&context(ADMIN, expired), burned, BurnReason::Voluntary).unwrap();
+        assert_eq!(liability(&contract), (0, 0));
+
+        // Renewed coverage counts again, once
+        let extra_seconds = 100 * contract.block_time();
+        contract.extend_warranty(&context(ADMIN, expired), covered.clone(), extra_seconds).unwrap();
+        assert_eq!(liability(&contract), (1, 500));
+        contract.extend_warranty(&context(ADMIN, expired), covered, extra_seconds).unwrap();
+        assert_eq!(liability(&contract), (1, 500));
+    }
+
+    #[test]
+    fn tag_scans_verify_against_the_key_registered_at_mint() {
+        let mut contract = contract();
+        let tag = signing_key(7);
+        let tagged = ProductData { tag_public_key: Some(public_key(&tag)), ..product_data(ADMIN, "SN-1") };
+        let product_id = mint_data(&mut contract, tagged, 10);
+        let token = contract.get_charm(&context(ADMIN, 20), product_id.clone()).unwrap();
+        let scan = |key: &k256::schnorr::SigningKey, challenge_block: u64| {
+            let mut data = token.generate_verification_data(challenge_block);
+            let message = crypto::tag_message(&product_id, &data.verification_hash, challenge_block);
+            data.tag_signature = Some(sign(key, &message));
+            data
+        };
+
+        let result = contract.verify_product(&context(3, 20), product_id.clone(), scan(&tag, 18)).unwrap();
+        assert!(result.physical_tag_verified);
+        assert!(!contract.verify_product(&context(3, 20), product_id.clone(), token.generate_verification_data(20)).unwrap().physical_tag_verified);
+
+        let forged = scan(&signing_key(8), 18);
+        assert_eq!(contract.verify_product(&context(3, 20), product_id.clone(), forged).unwrap_err(), VeriCharmError::InvalidTagSignature);
+        let stale = scan(&tag, 20 - TAG_SCAN_WINDOW_BLOCKS - 1);
+        assert_eq!(contract.verify_product(&context(3, 20), product_id, stale).unwrap_err(), VeriCharmError::InvalidTagSignature);
+    }
+
+    #[test]
+    fn signed_operations_advance_the_signer_nonce() {
+        let mut contract = contract();
+        let key = signing_key(ADMIN);
+        let ctx = context(ADMIN, 10);
+        let payload = b"operation";
+        let signature = sign(&key, &domain(ADMIN).message(0, payload));
+        let verify = |contract: &mut TestContract, ctx: &Context| {
+            contract.verify_signed_operation(ctx, &address(ADMIN), &address(ADMIN), &public_key(&key), payload, &signature)
+        };
+
+        let other_chain = Context { chain_id: "cardano".into(), ..context(ADMIN, 10) };
+        assert_eq!(verify(&mut contract, &other_chain), Err(VeriCharmError::InvalidSignature));
+        assert_eq!(verify(&mut contract, &ctx), Ok(()));
+        assert_eq!(contract.signer_nonce(address(ADMIN)), 1);
+        // Replays fail once the nonce has moved on
+        assert_eq!(verify(&mut contract, &ctx), Err(VeriCharmError::InvalidSignature));
+    }
+
+    #[test]
+    fn mints_require_the_manufacturers_signature_over_the_product_data() {
+        let mut contract = contract();
+        let mint_signed = |contract: &mut TestContract, product_data: ProductData| {
+            contract.mint_charm(&context(ADMIN, 10), product_data, metadata(), None).map(|token| token.id)
+        };
+
+        let forged = sign_product(2, product_data(ADMIN, "SN-1"));
+        assert_eq!(mint_signed(&mut contract, forged).unwrap_err(), VeriCharmError::InvalidSignature);
+        let mut tampered = product_data(ADMIN, "SN-1");
+        tampered.declared_value = 1_000;
+        assert_eq!(mint_signed(&mut contract, tampered).unwrap_err(), VeriCharmError::InvalidSignature);
+
+        let signed = product_data(ADMIN, "SN-1");
+        assert!(mint_signed(&mut contract, signed.clone()).is_ok());
+        assert_eq!(mint_signed(&mut contract, signed).unwrap_err(), VeriCharmError::ProductDataAlreadyMinted);
+    }
+
+    #[test]
+    fn burn_reasons_are_proven_against_a_recorded_commitment() {
+        let mut contract = contract();
+        let product_id = mint(&mut contract, "SN-1", 10);
+        contract.transfer_charm(&context(ADMIN, 11), product_id.clone(), address(2), None).unwrap();
+        let burned_at = 10 + contract.get_charm(&context(2, 11), product_id.clone()).unwrap().warranty_period;
+        let receipt = contract.burn_charm(&context(2, burned_at), product_id.clone(), BurnReason::Voluntary).unwrap();
+
+        let params = burn_reason_params();
+        let mut verifying_key = Vec::new();
+        params.vk.write(&mut verifying_key).unwrap();
+        contract.set_burn_reason_key(&context(ADMIN, burned_at), verifying_key).unwrap();
+
+        let secret = [7u8; 32];
+        let blind = burn_reason::burn_blind(&secret);
+        assert_eq!(
+            contract.commit_burn_reason(&context(3, burned_at), product_id.clone(), blind),
+            Err(VeriCharmError::NotTokenOwner),
+        );
+        let commitment = contract.commit_burn_reason(&context(2, burned_at), product_id.clone(), blind).unwrap();
+        assert_eq!(
+            contract.commit_burn_reason(&context(2, burned_at), product_id, blind),
+            Err(VeriCharmError::BurnAlreadyCommitted),
+        );
+
+        let proof = burn_reason::prove_burn_reason(&params, &receipt, &BurnReason::Voluntary, &secret).unwrap();
+        assert_eq!(contract.verify_burn_reason_proof(proof.clone(), commitment, BurnReason::Voluntary), Ok(true));
+        assert_eq!(contract.verify_burn_reason_proof(proof, commitment, BurnReason::RaffleEntry), Ok(false));
+
+        // A proof for a reason the burn didn't have opens no recorded commitment
+        let wrong = burn_reason::prove_burn_reason(&params, &receipt, &BurnReason::RaffleEntry, &secret).unwrap();
+        assert_eq!(contract.verify_burn_reason_proof(wrong.clone(), commitment, BurnReason::RaffleEntry), Ok(false));
+        let unrecorded = burn_reason::burn_commitment(&receipt.product_id, &BurnReason::RaffleEntry, &blind).unwrap();
+        assert_eq!(contract.verify_burn_reason_proof(wrong, unrecorded, BurnReason::RaffleEntry), Ok(false));
+    }
 }
@@ -0,0 +1,291 @@
+//! Incremental Merkle accumulator over minted product hashes
+//!
+//! Follows the fixed-depth append-only tree used by deposit contracts: the
+//! accumulator holds only the frontier, so the root is maintained in O(depth)
+//! per append, and empty subtrees hash to precomputed zero nodes. Nodes are
+//! written to a node store as their subtrees fill (two writes per append on
+//! average), so inclusion proofs take O(depth) reads. Leaf and interior
+//! hashes carry distinct prefixes, so an interior node can't pose as a leaf.
+
+use super::*;
+use crate::storage::Storage;
+
+/// Depth of the accumulator tree (supports 2^32 leaves)
+pub const TREE_DEPTH: usize = 32;
+
+/// Stored node position: height above the leaves, then index in the level.
+/// Height 0 holds the raw leaves.
+pub type NodeKey = (u8, u64);
+
+/// Hash a leaf into its tree node
+pub fn hash_leaf(leaf: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(leaf);
+    hasher.finalize().into()
+}
+
+/// Hash two child nodes into their parent
+pub fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Root of an empty subtree at each height
+fn zero_nodes() -> [Hash; TREE_DEPTH] {
+    let mut zeros = [[0u8; 32]; TREE_DEPTH];
+    for height in 1..TREE_DEPTH {
+        zeros[height] = hash_pair(&zeros[height - 1], &zeros[height - 1]);
+    }
+    zeros
+}
+
+/// Append-only Merkle accumulator
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MerkleAccumulator {
+    /// Number of leaves appended
+    count: u64,
+    /// Last completed left-hand node at each height
+    filled_subtrees: [Hash; TREE_DEPTH],
+}
+
+impl MerkleAccumulator {
+    /// Number of leaves appended so far
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    /// Whether no leaves have been appended
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Append a leaf, writing it and every node it completes to `nodes`.
+    /// Returns the leaf's index.
+    pub fn append(&mut self, leaf: Hash, nodes: &mut impl Storage<NodeKey, Hash>) -> u64 {
+        let index = self.count;
+        self.count += 1;
+        nodes.insert((0, index), leaf);
+
+        let mut node = hash_leaf(&leaf);
+        let mut position = index;
+        for height in 0..TREE_DEPTH {
+            if position & 1 == 0 {
+                self.filled_subtrees[height] = node;
+                break;
+            }
+            node = hash_pair(&self.filled_subtrees[height], &node);
+            position /= 2;
+            nodes.insert((height as u8 + 1, position), node);
+        }
+        index
+    }
+
+    /// Current root
+    pub fn root(&self) -> Hash {
+        let zeros = zero_nodes();
+        let mut node = zeros[0];
+        let mut size = self.count;
+        for height in 0..TREE_DEPTH {
+            node = if size & 1 == 1 {
+                hash_pair(&self.filled_subtrees[height], &node)
+            } else {
+                hash_pair(&node, &zeros[height])
+            };
+            size /= 2;
+        }
+        node
+    }
+
+    /// Inclusion proof for the leaf at `index`, read from the `nodes` this
+    /// accumulator was appended with
+    pub fn proof(&self, index: u64, nodes: &impl Storage<NodeKey, Hash>) -> Option<MerkleProof> {
+        prove(self.count, index, nodes)
+    }
+}
+
+/// Inclusion proof for the leaf at `index` in the tree of the first `count`
+/// leaves appended to `nodes`. Needs only the node store, so a finished tree
+/// can be proven against without keeping its accumulator.
+pub fn prove(count: u64, index: u64, nodes: &impl Storage<NodeKey, Hash>) -> Option<MerkleProof> {
+    if index >= count {
+        return None;
+    }
+    let node_at = |height: usize, position: u64| {
+        let node = nodes.get(&(height as u8, position))?;
+        Some(if height == 0 { hash_leaf(&node) } else { node })
+    };
+
+    let zeros = zero_nodes();
+    // Node right of the completed ones at the current height: the partially
+    // filled subtree on the rightmost path, or an empty one
+    let mut open = zeros[0];
+    let mut siblings = Vec::with_capacity(TREE_DEPTH);
+    for height in 0..TREE_DEPTH {
+        let completed = count >> height;
+        let sibling = (index >> height) ^ 1;
+        siblings.push(match sibling.cmp(&completed) {
+            core::cmp::Ordering::Less => node_at(height, sibling)?,
+            core::cmp::Ordering::Equal => open,
+            core::cmp::Ordering::Greater => zeros[height],
+        });
+        open = if completed & 1 == 1 {
+            hash_pair(&node_at(height, completed - 1)?, &open)
+        } else {
+            hash_pair(&open, &zeros[height])
+        };
+    }
+
+    Some(MerkleProof { leaf_index: index, leaf: nodes.get(&(0, index))?, siblings })
+}
+
+/// Merkle inclusion proof
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub leaf_index: u64,
+    pub leaf: Hash,
+    /// Sibling hashes from the leaf level upwards, exactly `TREE_DEPTH` of them
+    pub siblings: Vec<Hash>,
+}
+
+impl MerkleProof {
+    /// Check the proof against a trusted root
+    pub fn verify(&self, root: &Hash) -> bool {
+        if self.siblings.len() != TREE_DEPTH || self.leaf_index >> TREE_DEPTH != 0 {
+            return false;
+        }
+
+        let mut node = hash_leaf(&self.leaf);
+        let mut position = self.leaf_index;
+        for sibling in &self.siblings {
+            node = if position & 1 == 0 {
+                hash_pair(&node, sibling)
+            } else {
+                hash_pair(sibling, &node)
+            };
+            position /= 2;
+        }
+        node == *root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    fn leaf(value: u8) -> Hash {
+        [value; 32]
+    }
+
+    /// Root of a full-depth tree over `leaves`, padded with zero leaves
+    fn naive_root(leaves: &[Hash]) -> Hash {
+        let mut level: Vec<Hash> = leaves.iter().map(hash_leaf).collect();
+        let mut zero = [0u8; 32];
+        for _ in 0..TREE_DEPTH {
+            if level.is_empty() {
+                level.push(zero);
+            }
+            level = level
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&zero)))
+                .collect();
+            zero = hash_pair(&zero, &zero);
+        }
+        level[0]
+    }
+
+    fn accumulate(count: u8) -> (MerkleAccumulator, MemoryStorage<NodeKey, Hash>) {
+        let mut accumulator = MerkleAccumulator::default();
+        let mut nodes = MemoryStorage::default();
+        for value in 1..=count {
+            accumulator.append(leaf(value), &mut nodes);
+        }
+        (accumulator, nodes)
+    }
+
+    #[test]
+    fn root_matches_a_full_rebuild_after_every_append() {
+        let mut accumulator = MerkleAccumulator::default();
+        let mut nodes = MemoryStorage::default();
+        let mut leaves = Vec::new();
+        assert_eq!(accumulator.root(), naive_root(&leaves));
+        for value in 1..=9 {
+            assert_eq!(accumulator.append(leaf(value), &mut nodes), leaves.len() as u64);
+            leaves.push(leaf(value));
+            assert_eq!(accumulator.root(), naive_root(&leaves));
+        }
+        assert_eq!(accumulator.len(), 9);
+    }
+
+    #[test]
+    fn proofs_verify_against_the_current_root_only() {
+        for count in 1..=9 {
+            let (accumulator, nodes) = accumulate(count);
+            let root = accumulator.root();
+            for index in 0..u64::from(count) {
+                let proof = accumulator.proof(index, &nodes).unwrap();
+                assert_eq!(proof.leaf, leaf(index as u8 + 1));
+                assert!(proof.verify(&root));
+            }
+        }
+
+        let (mut accumulator, mut nodes) = accumulate(5);
+        let proof = accumulator.proof(2, &nodes).unwrap();
+        accumulator.append(leaf(6), &mut nodes);
+        assert!(!proof.verify(&accumulator.root()));
+        assert!(accumulator.proof(2, &nodes).unwrap().verify(&accumulator.root()));
+        assert!(accumulator.proof(6, &nodes).is_none());
+    }
+
+    #[test]
+    fn tampered_proofs_fail() {
+        let (accumulator, nodes) = accumulate(3);
+        let root = accumulator.root();
+
+        let mut wrong_leaf = accumulator.proof(1, &nodes).unwrap();
+        wrong_leaf.leaf = leaf(9);
+        assert!(!wrong_leaf.verify(&root));
+
+        let mut wrong_index = accumulator.proof(1, &nodes).unwrap();
+        wrong_index.leaf_index = 0;
+        assert!(!wrong_index.verify(&root));
+
+        let mut aliased_index = accumulator.proof(1, &nodes).unwrap();
+        aliased_index.leaf_index += 1 << TREE_DEPTH;
+        assert!(!aliased_index.verify(&root));
+    }
+
+    #[test]
+    fn proofs_must_have_exactly_tree_depth_siblings() {
+        let (accumulator, nodes) = accumulate(4);
+        let root = accumulator.root();
+        let proof = accumulator.proof(0, &nodes).unwrap();
+
+        let mut short = proof.clone();
+        short.siblings.pop();
+        assert!(!short.verify(&root));
+
+        let mut long = proof;
+        long.siblings.push([0u8; 32]);
+        assert!(!long.verify(&root));
+    }
+
+    #[test]
+    fn interior_nodes_do_not_verify_as_leaves() {
+        let (accumulator, nodes) = accumulate(4);
+        let root = accumulator.root();
+        let proof = accumulator.proof(0, &nodes).unwrap();
+
+        // Claim the node over leaves 0 and 1 is itself a leaf one level up
+        let forged = MerkleProof {
+            leaf_index: 0,
+            leaf: hash_pair(&hash_leaf(&leaf(1)), &hash_leaf(&leaf(2))),
+            siblings: proof.siblings[1..].iter().copied().chain([[0u8; 32]]).collect(),
+        };
+        assert!(!forged.verify(&root));
+    }
+}
@@ -0,0 +1,95 @@
+//! Gas-bounded iteration over contract maps
+//!
+//! Full scans can exceed block limits on large deployments, so scan-heavy
+//! admin operations process a bounded page per call and persist the returned
+//! cursor to resume in a later transaction.
+
+use crate::storage::Storage;
+
+/// Visit up to `max_items` entries of `map` whose key is after `cursor`, in
+/// key order. Returns the last visited key to resume from, or `None` once the
+/// map is exhausted. `max_items` is treated as at least 1.
+pub fn paginate_process<K, V, S, F>(
+    map: &S,
+    cursor: Option<&K>,
+    max_items: usize,
+    mut f: F,
+) -> Option<K>
+where
+    K: Ord + Clone,
+    S: Storage<K, V>,
+    F: FnMut(K, V),
+{
+    let max_items = max_items.max(1);
+    let mut last = None;
+    let mut processed = 0;
+
+    for (key, value) in map.iter_after(cursor) {
+        if processed == max_items {
+            return last;
+        }
+        last = Some(key.clone());
+        f(key, value);
+        processed += 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    fn store(keys: &[u32]) -> MemoryStorage<u32, u32> {
+        let mut storage = MemoryStorage::default();
+        for key in keys {
+            storage.insert(*key, key * 10);
+        }
+        storage
+    }
+
+    fn page(storage: &MemoryStorage<u32, u32>, cursor: Option<u32>, max_items: usize) -> (Vec<u32>, Option<u32>) {
+        let mut visited = Vec::new();
+        let next = paginate_process(storage, cursor.as_ref(), max_items, |key, value| {
+            assert_eq!(value, key * 10);
+            visited.push(key);
+        });
+        (visited, next)
+    }
+
+    #[test]
+    fn pages_resume_after_the_cursor() {
+        let storage = store(&[1, 2, 3, 4, 5]);
+        assert_eq!(page(&storage, None, 2), (vec![1, 2], Some(2)));
+        assert_eq!(page(&storage, Some(2), 2), (vec![3, 4], Some(4)));
+        assert_eq!(page(&storage, Some(4), 2), (vec![5], None));
+    }
+
+    #[test]
+    fn full_final_page_is_exhausted() {
+        let storage = store(&[1, 2]);
+        assert_eq!(page(&storage, None, 2), (vec![1, 2], None));
+        let storage = store(&[1, 2, 3]);
+        assert_eq!(page(&storage, None, 3), (vec![1, 2, 3], None));
+    }
+
+    #[test]
+    fn cursor_between_keys_seeks_to_the_next_key() {
+        let storage = store(&[10, 20, 30]);
+        assert_eq!(page(&storage, Some(15), 5), (vec![20, 30], None));
+        assert_eq!(page(&storage, Some(30), 5), (vec![], None));
+    }
+
+    #[test]
+    fn zero_page_size_visits_one_entry() {
+        let storage = store(&[1, 2]);
+        assert_eq!(page(&storage, None, 0), (vec![1], Some(1)));
+    }
+
+    #[test]
+    fn empty_storage_is_exhausted() {
+        let storage = store(&[]);
+        assert_eq!(page(&storage, None, 10), (vec![], None));
+    }
+}
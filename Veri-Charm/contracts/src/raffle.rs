@@ -0,0 +1,91 @@
+//! Raffle winner selection
+
+use super::*;
+
+/// Select a raffle winner from `entries`, weighted by `RaffleEntry::weight`.
+///
+/// Tie-break rule: entries are walked in ascending `entry_id` byte order, so
+/// the result never depends on storage iteration order. The draw target is the
+/// first 8 bytes of `seed` (big-endian) modulo the total weight, and the
+/// winner is the first entry whose cumulative weight exceeds the target. A
+/// target landing exactly on a cumulative boundary therefore goes to the next
+/// entry in `entry_id` order, and among equal-weight entries the lower
+/// `entry_id` always covers the lower range.
+///
+/// Returns the index of the winner in `entries`, or `None` if the total weight
+/// is zero.
+pub fn select_winner(entries: &[RaffleEntry], seed: &Hash) -> Option<usize> {
+    let total: u64 = entries.iter().map(|entry| entry.weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by(|a, b| entries[*a].entry_id.cmp(&entries[*b].entry_id));
+
+    let mut seed_bytes = [0u8; 8];
+    seed_bytes.copy_from_slice(&seed[..8]);
+    let target = u64::from_be_bytes(seed_bytes) % total;
+
+    let mut cumulative = 0u64;
+    order.into_iter().find(|index| {
+        cumulative += entries[*index].weight;
+        target < cumulative
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u8, weight: u64) -> RaffleEntry {
+        RaffleEntry {
+            participant: Address::default(),
+            product_id: format!("product-{}", id),
+            burn_time: 0,
+            entry_id: [id; 32],
+            weight,
+        }
+    }
+
+    fn seed(target: u64) -> Hash {
+        let mut seed = [0u8; 32];
+        seed[..8].copy_from_slice(&target.to_be_bytes());
+        seed
+    }
+
+    #[test]
+    fn zero_total_weight_has_no_winner() {
+        assert_eq!(select_winner(&[], &seed(0)), None);
+        assert_eq!(select_winner(&[entry(1, 0), entry(2, 0)], &seed(0)), None);
+    }
+
+    #[test]
+    fn boundary_targets_go_to_the_next_entry() {
+        let entries = [entry(1, 1), entry(2, 2)];
+        assert_eq!(select_winner(&entries, &seed(0)), Some(0));
+        assert_eq!(select_winner(&entries, &seed(1)), Some(1));
+        assert_eq!(select_winner(&entries, &seed(2)), Some(1));
+        // The target wraps modulo the total weight
+        assert_eq!(select_winner(&entries, &seed(3)), Some(0));
+    }
+
+    #[test]
+    fn winner_does_not_depend_on_input_order() {
+        let forward = [entry(1, 3), entry(2, 3), entry(3, 3)];
+        let reversed = [entry(3, 3), entry(2, 3), entry(1, 3)];
+        for target in 0..9 {
+            let winner = select_winner(&forward, &seed(target)).map(|index| forward[index].entry_id);
+            let reversed_winner = select_winner(&reversed, &seed(target)).map(|index| reversed[index].entry_id);
+            assert_eq!(winner, reversed_winner);
+        }
+    }
+
+    #[test]
+    fn zero_weight_entries_never_win() {
+        let entries = [entry(1, 0), entry(2, 5), entry(3, 0)];
+        for target in 0..5 {
+            assert_eq!(select_winner(&entries, &seed(target)), Some(1));
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Printable serial numbers with a check symbol
+//!
+//! Serials are 16 Crockford base32 symbols (80 bits of a salted hash) plus a
+//! mod-37 check symbol, so typos are caught before the chain is queried. Each
+//! mint mixes in the manufacturer's product signature, so codes can't be
+//! predicted from the public salt and the mint order.
+
+use super::*;
+
+/// Crockford base32 symbols; values 32-36 are check-only symbols
+const ALPHABET: &[u8; 37] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ*~$=U";
+
+/// Hash bytes encoded into a serial (16 symbols)
+const SERIAL_BYTES: usize = 10;
+
+/// Serial for the `counter`-th mint of `manufacturer` under `salt`, with
+/// `entropy` only the manufacturer could produce before the mint
+pub fn serial_code(salt: &Hash, manufacturer: &Address, counter: u64, entropy: &[u8]) -> String {
+    let digest = crypto::canonical_hash(&[
+        b"veri-charm/serial",
+        salt,
+        manufacturer.as_ref(),
+        &counter.to_be_bytes(),
+        entropy,
+    ]);
+
+    let mut code = String::with_capacity(SERIAL_BYTES * 8 / 5 + 1);
+    let mut buffer = 0u16;
+    let mut bits = 0;
+    for byte in &digest[..SERIAL_BYTES] {
+        buffer = (buffer << 8) | u16::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            code.push(ALPHABET[usize::from((buffer >> bits) & 0x1f)] as char);
+        }
+    }
+    code.push(check_symbol(&code));
+    code
+}
+
+/// Canonical form of a scanned serial: uppercase, hyphens and spaces dropped,
+/// and the Crockford look-alikes I/L and O read as 1 and 0
+pub fn normalize_serial(serial: &str) -> String {
+    serial
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .map(|c| match c.to_ascii_uppercase() {
+            'I' | 'L' => '1',
+            'O' => '0',
+            c => c,
+        })
+        .collect()
+}
+
+/// Mod-37 check symbol over the base32 value of `code`
+fn check_symbol(code: &str) -> char {
+    let remainder = code.bytes().fold(0u32, |acc, symbol| {
+        let value = ALPHABET.iter().position(|a| *a == symbol).unwrap_or(0) as u32;
+        (acc * 32 + value) % 37
+    });
+    ALPHABET[remainder as usize] as char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serial(counter: u64, entropy: &[u8]) -> String {
+        serial_code(&[7u8; 32], &Address::default(), counter, entropy)
+    }
+
+    #[test]
+    fn serials_are_sixteen_symbols_and_a_valid_check() {
+        let code = serial(1, b"signature");
+        assert_eq!(code.len(), SERIAL_BYTES * 8 / 5 + 1);
+        let (body, check) = code.split_at(code.len() - 1);
+        assert!(body.bytes().all(|symbol| ALPHABET[..32].contains(&symbol)));
+        assert_eq!(check.chars().next(), Some(check_symbol(body)));
+    }
+
+    #[test]
+    fn counter_and_entropy_change_the_serial() {
+        assert_eq!(serial(1, b"signature"), serial(1, b"signature"));
+        assert_ne!(serial(1, b"signature"), serial(2, b"signature"));
+        assert_ne!(serial(1, b"signature"), serial(1, b"other signature"));
+    }
+
+    #[test]
+    fn check_symbol_catches_every_single_substitution() {
+        let code = serial(42, b"signature");
+        let (body, check) = code.split_at(code.len() - 1);
+        let check = check.chars().next().unwrap();
+        for position in 0..body.len() {
+            for symbol in &ALPHABET[..32] {
+                let mut typo = body.as_bytes().to_vec();
+                if typo[position] == *symbol {
+                    continue;
+                }
+                typo[position] = *symbol;
+                let typo = String::from_utf8(typo).unwrap();
+                assert_ne!(check_symbol(&typo), check);
+            }
+        }
+    }
+
+    #[test]
+    fn normalization_folds_case_separators_and_look_alikes() {
+        assert_eq!(normalize_serial("ab-cd ef"), "ABCDEF");
+        assert_eq!(normalize_serial("iLo"), "110");
+        let code = serial(3, b"signature");
+        let printed = format!("{}-{}", &code[..8], &code[8..]).to_lowercase();
+        assert_eq!(normalize_serial(&printed), code);
+    }
+}
@@ -0,0 +1,118 @@
+//! Ordered key-value storage abstraction
+//!
+//! Contract state lives in maps chosen by a `StorageBackend`: the SDK `Map`
+//! on-chain, `MemoryStorage` in tests, so entry points run without a host.
+
+use super::*;
+use core::ops::Bound::{self, Excluded, Unbounded};
+use serde::de::DeserializeOwned;
+
+/// Ordered key-value store
+pub trait Storage<K, V> {
+    fn get(&self, key: &K) -> Option<V>;
+    fn insert(&mut self, key: K, value: V);
+    fn remove(&mut self, key: &K) -> Option<V>;
+    fn contains_key(&self, key: &K) -> bool;
+    /// Entries in key order
+    fn iter(&self) -> impl Iterator<Item = (K, V)> + '_;
+    /// Entries with keys strictly after `cursor` (all entries for `None`), in
+    /// key order, seeking to the cursor rather than scanning up to it
+    fn iter_after(&self, cursor: Option<&K>) -> impl Iterator<Item = (K, V)> + '_;
+}
+
+/// Family of stores the contract keeps its maps in
+pub trait StorageBackend {
+    type Map<K, V>: Storage<K, V> + Default + Serialize + DeserializeOwned
+    where
+        K: Ord + Clone + Serialize + DeserializeOwned,
+        V: Clone + Serialize + DeserializeOwned;
+}
+
+/// Host storage through the SDK `Map`
+#[derive(Default)]
+pub struct HostStorage;
+
+impl StorageBackend for HostStorage {
+    type Map<K, V> = Map<K, V>
+    where
+        K: Ord + Clone + Serialize + DeserializeOwned,
+        V: Clone + Serialize + DeserializeOwned;
+}
+
+/// Lower bound of the keys after `cursor`
+fn after<K: Clone>(cursor: Option<&K>) -> (Bound<K>, Bound<K>) {
+    (cursor.map_or(Unbounded, |cursor| Excluded(cursor.clone())), Unbounded)
+}
+
+impl<K: Ord + Clone, V: Clone> Storage<K, V> for Map<K, V> {
+    fn get(&self, key: &K) -> Option<V> {
+        Map::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        Map::insert(self, key, value);
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        Map::remove(self, key)
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        Map::contains_key(self, key)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (K, V)> + '_ {
+        Map::iter(self)
+    }
+
+    fn iter_after(&self, cursor: Option<&K>) -> impl Iterator<Item = (K, V)> + '_ {
+        Map::range(self, after(cursor))
+    }
+}
+
+/// In-memory backend standing in for host storage in tests
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryBackend;
+
+#[cfg(test)]
+impl StorageBackend for MemoryBackend {
+    type Map<K, V> = MemoryStorage<K, V>
+    where
+        K: Ord + Clone + Serialize + DeserializeOwned,
+        V: Clone + Serialize + DeserializeOwned;
+}
+
+/// In-memory store standing in for host storage in tests
+#[cfg(test)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MemoryStorage<K: Ord, V> {
+    entries: BTreeMap<K, V>,
+}
+
+#[cfg(test)]
+impl<K: Ord + Clone, V: Clone> Storage<K, V> for MemoryStorage<K, V> {
+    fn get(&self, key: &K) -> Option<V> {
+        self.entries.get(key).cloned()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.entries.insert(key, value);
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.entries.remove(key)
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (K, V)> + '_ {
+        self.entries.iter().map(|(key, value)| (key.clone(), value.clone()))
+    }
+
+    fn iter_after(&self, cursor: Option<&K>) -> impl Iterator<Item = (K, V)> + '_ {
+        self.entries.range(after(cursor)).map(|(key, value)| (key.clone(), value.clone()))
+    }
+}
@@ -0,0 +1,147 @@
+//! Fixtures shared by the unit tests
+
+use super::*;
+use crate::storage::MemoryBackend;
+use crate::burn_reason::BurnReasonCircuit;
+use bellman::groth16::{self, Parameters};
+use bls12_381::Bls12;
+use k256::schnorr::{signature::Signer, SigningKey};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Contract running against in-memory storage
+pub type TestContract = VeriCharmContract<MemoryBackend>;
+
+/// Chain every test context runs on
+pub const CHAIN_ID: &str = "bitcoin";
+
+/// Deployer of the test contract, also its first verified manufacturer
+pub const ADMIN: u8 = 1;
+
+/// Distinct address built from a repeated byte
+pub fn address(byte: u8) -> Address {
+    Address::from([byte; 32])
+}
+
+/// Address the test contract is deployed at
+pub fn contract_address() -> Address {
+    address(0xcc)
+}
+
+/// Call from `sender` at `block_height` with no value attached
+pub fn context(sender: u8, block_height: u64) -> Context {
+    let sender = address(sender);
+    Context {
+        tx_hash: crypto::canonical_hash(&[sender.as_ref(), &block_height.to_be_bytes()]),
+        sender,
+        block_height,
+        chain_id: CHAIN_ID.into(),
+        contract_address: contract_address(),
+        value: 0,
+    }
+}
+
+/// Schnorr key derived from a repeated byte
+pub fn signing_key(byte: u8) -> SigningKey {
+    SigningKey::from_bytes(&[byte; 32]).unwrap()
+}
+
+/// x-only public key of `key`
+pub fn public_key(key: &SigningKey) -> PublicKey {
+    key.verifying_key().to_bytes().to_vec()
+}
+
+/// BIP-340 signature by `key` over `message`
+pub fn sign(key: &SigningKey, message: &[u8]) -> Signature {
+    key.sign(message).to_bytes().to_vec()
+}
+
+/// Signing domain of the test deployment for `manufacturer`
+pub fn domain(manufacturer: u8) -> SigningDomain {
+    SigningDomain {
+        chain_id: CHAIN_ID.into(),
+        contract: contract_address(),
+        manufacturer: address(manufacturer),
+    }
+}
+
+/// Product data for `serial_number`, signed by `manufacturer`'s key
+pub fn product_data(manufacturer: u8, serial_number: &str) -> ProductData {
+    let product_data = ProductData {
+        name: String::from("Test Product"),
+        category: String::from("test"),
+        serial_number: serial_number.into(),
+        batch_id: String::from("batch-1"),
+        tag_public_key: None,
+        quantity: None,
+        expiry_block: None,
+        declared_value: 0,
+        sku_prefix: None,
+        allowed_regions: Vec::new(),
+        product_signature: Vec::new(),
+    };
+    sign_product(manufacturer, product_data)
+}
+
+/// `product_data` with its signature replaced by `manufacturer`'s
+pub fn sign_product(manufacturer: u8, mut product_data: ProductData) -> ProductData {
+    let message = domain(manufacturer).product_message(&product_data.signing_payload());
+    product_data.product_signature = sign(&signing_key(manufacturer), &message);
+    product_data
+}
+
+/// Mint `product_data` as `ADMIN` at `block_height`
+pub fn mint_data(contract: &mut TestContract, product_data: ProductData, block_height: u64) -> ProductId {
+    contract
+        .mint_charm(&context(ADMIN, block_height), sign_product(ADMIN, product_data), metadata(), None)
+        .unwrap()
+        .id
+}
+
+/// Metadata with every option off
+pub fn metadata() -> TokenMetadata {
+    TokenMetadata {
+        description: String::from("test token"),
+        warranty_transferable: false,
+        require_registered_retailer: false,
+        reset_warranty_on_resale: false,
+    }
+}
+
+/// Contract initialised at block 1 by `ADMIN`, whose signing key is set
+pub fn contract() -> TestContract {
+    let mut contract = TestContract::default();
+    contract.init(&context(ADMIN, 1)).unwrap();
+    contract.set_manufacturer_key(&context(ADMIN, 1), public_key(&signing_key(ADMIN))).unwrap();
+    contract
+}
+
+/// Mint `serial_number` as `ADMIN` at `block_height`
+pub fn mint(contract: &mut TestContract, serial_number: &str, block_height: u64) -> ProductId {
+    mint_data(contract, product_data(ADMIN, serial_number), block_height)
+}
+
+/// Verifier inputs for `charm_token` with no proof, tag scan or archived history
+pub fn verification_data(charm_token: &CharmToken) -> VerificationData {
+    VerificationData {
+        product_id: charm_token.id.clone(),
+        manufacturer: charm_token.manufacturer.clone(),
+        current_owner: charm_token.current_owner.clone(),
+        mint_time: charm_token.mint_time,
+        warranty_valid: true,
+        verification_hash: charm_token.calculate_verification_hash(),
+        zk_proof: None,
+        public_inputs: Vec::new(),
+        nullifier: None,
+        tag_signature: None,
+        tag_challenge_block: 0,
+        manufacturer_proof: None,
+        manufacturer_public_inputs: Vec::new(),
+        provenance_proof: Vec::new(),
+    }
+}
+
+/// Burn-reason circuit parameters generated from a fixed seed
+pub fn burn_reason_params() -> Parameters<Bls12> {
+    let mut rng = StdRng::seed_from_u64(7);
+    groth16::generate_random_parameters::<Bls12, _, _>(BurnReasonCircuit::default(), &mut rng).unwrap()
+}
@@ -0,0 +1,687 @@
+//! Shared data types for the Veri-Charm contract
+
+use super::*;
+use crate::crypto::{PublicKey, Signature};
+use crate::merkle::MerkleAccumulator;
+use crate::verification::{Field, ZkProof};
+
+/// Unique product identifier
+pub type ProductId = String;
+/// Cross-chain beam identifier
+pub type BeamId = Hash;
+/// UTXO chain identifier (e.g. "bitcoin-testnet", "cardano-testnet")
+pub type ChainId = String;
+/// Warranty claim identifier, assigned sequentially
+pub type ClaimId = u64;
+
+/// Physical product attributes recorded at mint
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProductData {
+    pub name: String,
+    pub category: String,
+    pub serial_number: String,
+    pub batch_id: String,
+    /// Public key of the product's NFC anti-counterfeit tag, if fitted
+    #[serde(default)]
+    pub tag_public_key: Option<PublicKey>,
+    /// Initial quantity for bulk goods tracked under one token
+    #[serde(default)]
+    pub quantity: Option<u64>,
+    /// Block after which a perishable product expires
+    #[serde(default)]
+    pub expiry_block: Option<u64>,
+    /// Declared refundable value, counted toward warranty liability
+    #[serde(default)]
+    pub declared_value: u64,
+    /// SKU prefix for the product ID, counted separately per prefix
+    #[serde(default)]
+    pub sku_prefix: Option<String>,
+    /// Retailer regions the product may be sold in; empty allows every region
+    #[serde(default)]
+    pub allowed_regions: Vec<String>,
+    /// Manufacturer's signature over `SigningDomain::product_message` of
+    /// `signing_payload`, checked at mint; each signed payload mints once
+    #[serde(default)]
+    pub product_signature: Signature,
+}
+
+impl ProductData {
+    /// Bytes the manufacturer signs: these product data with the signature
+    /// field emptied
+    pub fn signing_payload(&self) -> Vec<u8> {
+        let unsigned = ProductData {
+            product_signature: Vec::new(),
+            ..self.clone()
+        };
+        serde_json::to_vec(&unsigned).unwrap_or_default()
+    }
+
+    /// Reject product data that would be stored on-chain malformed: an empty
+    /// name, oversized string fields or a zero quantity
+    pub fn validate(&self) -> Result<(), VeriCharmError> {
+        if self.name.trim().is_empty() {
+            return Err(VeriCharmError::InvalidProductData);
+        }
+        let fields = [&self.name, &self.category, &self.serial_number, &self.batch_id];
+        if fields.iter().any(|field| field.len() > MAX_PRODUCT_FIELD_LEN) {
+            return Err(VeriCharmError::InvalidProductData);
+        }
+        if self.sku_prefix.as_ref().is_some_and(|prefix| prefix.len() > MAX_PRODUCT_FIELD_LEN) {
+            return Err(VeriCharmError::InvalidProductData);
+        }
+        if self.allowed_regions.iter().any(|region| region.is_empty() || region.len() > MAX_PRODUCT_FIELD_LEN) {
+            return Err(VeriCharmError::InvalidProductData);
+        }
+        if self.quantity == Some(0) || self.expiry_block == Some(0) {
+            return Err(VeriCharmError::InvalidProductData);
+        }
+        Ok(())
+    }
+
+    /// Product name bounded for log output, with a hash of the full name so
+    /// truncated entries can still be traced
+    pub fn log_name(&self) -> String {
+        if self.name.len() <= MAX_LOG_NAME_LEN {
+            return self.name.clone();
+        }
+        let mut end = MAX_LOG_NAME_LEN;
+        while !self.name.is_char_boundary(end) {
+            end -= 1;
+        }
+        let name_hash = Sha256::digest(self.name.as_bytes());
+        format!("{}... (sha256:{})", &self.name[..end], hex::encode(name_hash))
+    }
+}
+
+/// Descriptive token metadata
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TokenMetadata {
+    pub description: String,
+    /// Whether remaining warranty carries over to secondhand buyers
+    #[serde(default)]
+    pub warranty_transferable: bool,
+    /// Only allow transfers to registered retailers
+    #[serde(default)]
+    pub require_registered_retailer: bool,
+    /// Restart the full warranty period on each resale instead of carrying
+    /// the remainder over
+    #[serde(default)]
+    pub reset_warranty_on_resale: bool,
+}
+
+/// Registered manufacturer
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Manufacturer {
+    pub address: Address,
+    pub name: String,
+    pub verified: bool,
+    pub products_minted: u64,
+    /// If set, tokens must be verified within this many blocks before transfer
+    #[serde(default)]
+    pub require_recent_verification: Option<u64>,
+    /// Branded product ID prefix used instead of the address
+    #[serde(default)]
+    pub id_namespace: Option<String>,
+    /// Free-form tag attached to this manufacturer's token events
+    #[serde(default)]
+    pub event_tag: Option<String>,
+    /// Warranty granted at mint in seconds; 0 uses `WARRANTY_PERIOD_SECS`
+    #[serde(default)]
+    pub default_warranty: u64,
+    /// Key that signs the manufacturer's product data
+    #[serde(default)]
+    pub public_key: Option<PublicKey>,
+    /// Set when the admin revokes the manufacturer; a revoked manufacturer
+    /// can't be verified again
+    #[serde(default)]
+    pub revoked: bool,
+}
+
+impl Manufacturer {
+    /// New manufacturer record with no mints and default settings
+    pub fn new(address: Address, name: String, verified: bool) -> Self {
+        Manufacturer {
+            address,
+            name,
+            verified,
+            products_minted: 0,
+            require_recent_verification: None,
+            id_namespace: None,
+            event_tag: None,
+            default_warranty: 0,
+            public_key: None,
+            revoked: false,
+        }
+    }
+    
+    /// Public summary of this manufacturer
+    pub fn info(&self) -> ManufacturerInfo {
+        ManufacturerInfo {
+            name: self.name.clone(),
+            verified: self.verified,
+            products_minted: self.products_minted,
+        }
+    }
+}
+
+/// Manufacturer summary reported by `get_manufacturer`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ManufacturerInfo {
+    pub name: String,
+    pub verified: bool,
+    pub products_minted: u64,
+}
+
+/// Registered retailer
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Retailer {
+    pub address: Address,
+    pub name: String,
+    pub region: String,
+    /// Tokens transferred to this retailer
+    #[serde(default)]
+    pub tokens_received: u64,
+    /// Tokens this retailer transferred onward
+    #[serde(default)]
+    pub tokens_sold: u64,
+    /// Tokens currently held
+    #[serde(default)]
+    pub tokens_held: u64,
+}
+
+impl Retailer {
+    /// New retailer record with zeroed counters
+    pub fn new(address: Address, name: String, region: String) -> Self {
+        Retailer {
+            address,
+            name,
+            region,
+            tokens_received: 0,
+            tokens_sold: 0,
+            tokens_held: 0,
+        }
+    }
+
+    /// Current sales counters
+    pub fn stats(&self) -> RetailerStats {
+        RetailerStats {
+            received: self.tokens_received,
+            sold: self.tokens_sold,
+            held: self.tokens_held,
+        }
+    }
+}
+
+/// Sales counters reported by `retailer_stats`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RetailerStats {
+    pub received: u64,
+    pub sold: u64,
+    pub held: u64,
+}
+
+/// Inputs supplied by a verifier to `verify_product`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VerificationData {
+    pub product_id: ProductId,
+    pub manufacturer: Address,
+    pub current_owner: Address,
+    pub mint_time: u64,
+    pub warranty_valid: bool,
+    pub verification_hash: Hash,
+    /// Optional ZK proof of authenticity
+    pub zk_proof: Option<ZkProof>,
+    /// Public inputs for the ZK proof
+    pub public_inputs: Vec<Field>,
+    /// One-time tag derived from the proof; required with a proof and must
+    /// be one of its public inputs so it can't be swapped
+    pub nullifier: Option<Hash>,
+    /// Signature produced by the product's NFC tag when scanned
+    pub tag_signature: Option<Signature>,
+    /// Block the tag was challenged with; must be recent when verified
+    pub tag_challenge_block: u64,
+    /// Separate proof under the manufacturer's own circuit, for a brand attestation
+    pub manufacturer_proof: Option<ZkProof>,
+    /// Public inputs for the manufacturer's proof; must include the token's
+    /// verification hash reduced into the field
+    pub manufacturer_public_inputs: Vec<Field>,
+    /// Archived transfer records rolled up into the token's `history_root`
+    pub provenance_proof: Vec<TransferRecord>,
+}
+
+/// How much of a token's ownership its owner lets `verify_product` reveal
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PrivacyLevel {
+    #[default]
+    Public,
+    /// Owner shown as a per-product hash of their address
+    PseudonymousHash,
+    Private,
+}
+
+/// Current owner as rendered under their privacy level
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum OwnerView {
+    Public(Address),
+    /// `sha256(owner || product_id)`, stable per product but unlinkable across products
+    Pseudonymous(Hash),
+    Private,
+}
+
+impl OwnerView {
+    /// Render `owner` of `product_id` under `level`
+    pub fn render(owner: &Address, product_id: &ProductId, level: &PrivacyLevel) -> Self {
+        match level {
+            PrivacyLevel::Public => OwnerView::Public(owner.clone()),
+            PrivacyLevel::PseudonymousHash => {
+                let mut hasher = Sha256::new();
+                hasher.update(owner);
+                hasher.update(product_id.as_bytes());
+                OwnerView::Pseudonymous(hasher.finalize().into())
+            }
+            PrivacyLevel::Private => OwnerView::Private,
+        }
+    }
+}
+
+/// Transfer record with both parties rendered under the owner's privacy level
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransferView {
+    pub from: OwnerView,
+    pub to: OwnerView,
+    pub timestamp: u64,
+    /// Only shown to public owners, as the transaction names both parties
+    pub tx_hash: Option<Hash>,
+}
+
+impl TransferView {
+    /// Render `record` of `product_id` under `level`
+    pub fn render(record: &TransferRecord, product_id: &ProductId, level: &PrivacyLevel) -> Self {
+        TransferView {
+            from: OwnerView::render(&record.from, product_id, level),
+            to: OwnerView::render(&record.to, product_id, level),
+            timestamp: record.timestamp,
+            tx_hash: (*level == PrivacyLevel::Public).then_some(record.tx_hash),
+        }
+    }
+}
+
+/// Why `verify_product` judged a product inauthentic
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AuthenticityFailure {
+    SupplyChainBroken,
+    /// Manufacturer's verification was revoked
+    ManufacturerRevoked,
+    /// Manufacturer was never verified, or not yet
+    ManufacturerUnverified,
+    ProofInvalid,
+    Recalled,
+}
+
+/// Outcome of `verify_product`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VerificationResult {
+    pub product_id: ProductId,
+    pub is_authentic: bool,
+    /// First failed check when `is_authentic` is false
+    pub authenticity_reason: Option<AuthenticityFailure>,
+    pub manufacturer: Address,
+    pub current_owner: OwnerView,
+    pub warranty_valid: bool,
+    pub verification_time: u64,
+    /// Whether the physical NFC tag signed this verification
+    pub physical_tag_verified: bool,
+    /// Tokens this one replaces, nearest predecessor first
+    pub recall_lineage: Vec<ProductId>,
+    /// Average blocks held per owner, for resale-velocity analytics
+    pub average_holding_blocks: u64,
+    /// Whether a separate proof verified under the manufacturer's own circuit
+    pub manufacturer_attested: bool,
+    /// Attestation bound to the manufacturer's verifying key, when attested
+    pub manufacturer_attestation: Option<Hash>,
+    /// 0-100 score from `verification::confidence_score`
+    pub confidence_score: u8,
+    /// ZK verification was disabled and any proof was ignored
+    pub zk_skipped: bool,
+    /// Manufacturer's reason, if the product has been recalled
+    pub recall_reason: Option<String>,
+    /// Result of `CharmToken::verify_route`; false flags grey-market diversion
+    pub route_valid: bool,
+}
+
+/// Reason a transfer is blocked, reported by `transfer_preflight`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TransferBlock {
+    ProductNotFound,
+    NotOwner,
+    Burned,
+    Frozen,
+    VerificationStale,
+    RecipientNotParticipant,
+    Blacklisted,
+    UnregisteredRetailer,
+    Locked,
+    Recalled,
+    Bundled,
+    RegionNotAllowed,
+    /// The contract is paused
+    Paused,
+}
+
+/// Who may blacklist a manufacturer's token
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BlacklistPolicy {
+    /// The admin alone
+    #[default]
+    AdminOnly,
+    /// The admin, once the token's manufacturer has consented
+    AdminWithManufacturerConsent,
+    /// Only the dispute arbitrator
+    ArbitratorOnly,
+}
+
+/// Cross-chain beam lifecycle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum BeamStatus {
+    Initiated,
+    Locked,
+    Completed,
+    /// Timed out before completion; superseded by a retry
+    Expired,
+    /// Timed out and cancelled by the sender, releasing the token
+    Cancelled,
+}
+
+/// Cross-chain beam record
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CrossChainBeam {
+    pub beam_id: BeamId,
+    pub product_id: ProductId,
+    pub source_chain: ChainId,
+    pub target_chain: ChainId,
+    pub sender: Address,
+    pub beam_time: u64,
+    pub status: BeamStatus,
+    pub lock_tx_hash: Option<Hash>,
+    pub unlock_tx_hash: Option<Hash>,
+    /// Block after which the beam expires if not completed
+    #[serde(default)]
+    pub timeout_height: u64,
+    /// 1-based attempt number for this product
+    #[serde(default)]
+    pub attempt: u32,
+    /// Attempt limit in force when the beam was created
+    #[serde(default)]
+    pub max_attempts: u32,
+    /// Serialization version of the token payload carried by the beam
+    #[serde(default)]
+    pub payload_version: u16,
+}
+
+/// Per-chain parameters for beams targeting that chain
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ChainInfo {
+    /// Confirmations the unlock tx needs before a beam may complete
+    pub required_confirmations: u64,
+}
+
+/// Relayed evidence that a beam's unlock tx was included on the target chain
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UnlockProof {
+    pub unlock_tx_hash: Hash,
+    /// Block height the unlock tx was included at
+    pub included_at: u64,
+}
+
+/// Beam request parameters
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BeamData {
+    pub recipient_address: String,
+}
+
+/// Beam receipt returned to the sender
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BeamReceipt {
+    pub beam_id: BeamId,
+    pub lock_script: Vec<u8>,
+    pub timeout_height: u64,
+}
+
+/// Sequential audit record identifier
+pub type AuditId = u64;
+
+/// Regulatory verification evidence, hash-chained to the previous record
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AuditRecord {
+    pub audit_id: AuditId,
+    pub auditor: Address,
+    pub product_id: ProductId,
+    pub block_height: u64,
+    pub result: VerificationResult,
+    /// Hash of the previous record (zero for the first)
+    pub prev_hash: Hash,
+    /// Hash over this record's fields and `prev_hash`
+    pub record_hash: Hash,
+}
+
+impl AuditRecord {
+    /// Compute the chained hash for a record, covering the full
+    /// verification result so no part of the evidence can be altered
+    pub fn compute_hash(&self) -> Hash {
+        let result = serde_json::to_vec(&self.result).unwrap_or_default();
+        crypto::canonical_hash(&[
+            b"veri-charm/audit-record",
+            &self.prev_hash,
+            &self.audit_id.to_be_bytes(),
+            self.auditor.as_ref(),
+            self.product_id.as_bytes(),
+            &self.block_height.to_be_bytes(),
+            &result,
+        ])
+    }
+}
+
+/// Listing of an owner's tokens for wallet migration or backup. It is not
+/// signed: anyone can produce a matching checksum, so entries must be checked
+/// against the chain before they are trusted.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OwnerBundle {
+    pub owner: Address,
+    pub chain_id: ChainId,
+    pub contract: Address,
+    pub block_height: u64,
+    /// (product id, verification hash) for each live token the owner holds
+    pub entries: Vec<(ProductId, Hash)>,
+    /// Checksum over all of the above, to catch corruption in storage or transit
+    pub checksum: Hash,
+}
+
+impl OwnerBundle {
+    /// Recompute the checksum from the bundle contents
+    pub fn compute_checksum(&self) -> Hash {
+        let block_height = self.block_height.to_be_bytes();
+        let header: [&[u8]; 5] = [
+            b"veri-charm/owner-bundle",
+            self.owner.as_ref(),
+            self.chain_id.as_bytes(),
+            self.contract.as_ref(),
+            &block_height,
+        ];
+        let mut fields = header.to_vec();
+        for (product_id, verification_hash) in &self.entries {
+            fields.push(product_id.as_bytes());
+            fields.push(verification_hash);
+        }
+        crypto::canonical_hash(&fields)
+    }
+
+    /// Whether the checksum matches the contents. Detects corruption, not
+    /// tampering.
+    pub fn is_intact(&self) -> bool {
+        self.checksum == self.compute_checksum()
+    }
+}
+
+/// Arbitrator's final ruling on an escalated counterfeit dispute
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Ruling {
+    /// Token is genuine; freeze lifted and reports cleared
+    Authentic,
+    /// Token is counterfeit; it stays frozen permanently
+    Counterfeit,
+}
+
+/// Counterfeit dispute escalated past the manufacturer to the arbitrator
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Dispute {
+    pub product_id: ProductId,
+    pub reporter: Address,
+    pub escalated_at: u64,
+    pub ruling: Option<Ruling>,
+}
+
+/// Outcome of `verify_batch`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatchVerification {
+    /// Per-item outcomes in input order; stops at the first failure in fail-fast mode
+    pub results: Vec<Result<VerificationResult, VeriCharmError>>,
+    /// Index and reason of the first item that failed, if any
+    pub first_failure: Option<(u32, VeriCharmError)>,
+}
+
+/// Outstanding warranty exposure of a manufacturer
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Liability {
+    /// Tokens whose warranty liability has not been released
+    pub token_count: u64,
+    /// Sum of their declared values
+    pub total_value: u64,
+}
+
+/// Core state invariant found broken by `check_invariants`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// `total_minted + total_derived - total_burned` disagrees with the live token count
+    SupplyMismatch { expected: u64, found: u64 },
+    /// Token whose manufacturer is not registered
+    MissingManufacturer(ProductId),
+    /// Burned token still marked frozen
+    BurnedAndFrozen(ProductId),
+    /// Beam record for a token that does not exist
+    OrphanBeam(BeamId),
+}
+
+/// Progress of a resumable `check_invariants` scan; start from `Default`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct InvariantScan {
+    pub product_cursor: Option<ProductId>,
+    /// Whether every token has been visited
+    pub products_done: bool,
+    pub beam_cursor: Option<BeamId>,
+    /// Live tokens counted so far
+    pub active_count: u64,
+}
+
+/// One page of `check_invariants`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InvariantReport {
+    pub violations: Vec<InvariantViolation>,
+    /// Scan state to pass to the next call, or `None` once finished
+    pub next: Option<InvariantScan>,
+}
+
+/// Oracle's signed statement that an external transfer condition holds
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OracleAttestation {
+    /// Condition attested, e.g. "export-license"
+    pub condition: String,
+    /// Last block the attestation is valid for
+    pub valid_until: u64,
+    pub oracle_key: PublicKey,
+    pub signature: Signature,
+}
+
+/// Ownership chain returned by `get_provenance`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Provenance {
+    pub manufacturer: Address,
+    pub mint_time: u64,
+    /// Current owner as their privacy level allows
+    pub current_owner: OwnerView,
+    /// Live transfer records, oldest first, with parties rendered like
+    /// `current_owner`
+    pub transfers: Vec<TransferView>,
+    /// Result of `CharmToken::verify_supply_chain`
+    pub chain_intact: bool,
+}
+
+/// Warranty coverage reported by `warranty_status`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WarrantyStatus {
+    pub active: bool,
+    /// Block at which coverage ends
+    pub expires_at: u64,
+    /// Blocks of coverage left, zero once expired
+    pub remaining: u64,
+}
+
+/// Page of product IDs returned by `list_products`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProductPage {
+    pub product_ids: Vec<ProductId>,
+    /// Mint position to pass as `start` for the next page, if any
+    pub next_cursor: Option<u64>,
+}
+
+/// Challenge returned by `prove_ownership`. The owner proves against
+/// `challenge`, `commitment` and a digest of `current_owner` that the contract
+/// supplies itself, so the owner's address never appears in the proof or result.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OwnershipChallenge {
+    pub product_id: ProductId,
+    /// Prover's commitment to their hidden address and a blinding secret
+    pub commitment: Hash,
+    /// Binds the commitment to the token's current state; stale after a transfer
+    pub challenge: Hash,
+}
+
+/// Warranty claim filed by a token owner; the token is kept, not burned
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WarrantyClaim {
+    pub product_id: ProductId,
+    pub claimant: Address,
+    pub description: String,
+    pub filed_at: u64,
+    /// Manufacturer's resolution note and block, once closed
+    pub resolution: Option<(u64, String)>,
+}
+
+/// Published snapshot of every token's verification hash, in product id
+/// order. The tree itself stays in the contract's snapshot buffers.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StateSnapshot {
+    pub root: Hash,
+    /// Number of leaves, one per token
+    pub token_count: u64,
+    /// `snapshot_id_set_hash` chained over the product ids in leaf order
+    pub id_set_hash: Hash,
+    /// Which of the contract's snapshot buffers holds the tree
+    pub buffer: u8,
+}
+
+/// Snapshot scan in progress
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SnapshotScan {
+    pub accumulator: MerkleAccumulator,
+    /// Hash of the product ids scanned so far
+    pub id_set_hash: Hash,
+    /// Buffer the tree is being written to
+    pub buffer: u8,
+    /// Last product scanned
+    pub cursor: Option<ProductId>,
+}
+
+/// Extend a snapshot's id-set hash with the next product id
+pub fn snapshot_id_set_hash(id_set_hash: &Hash, product_id: &ProductId) -> Hash {
+    crypto::canonical_hash(&[b"veri-charm/snapshot-ids", id_set_hash, product_id.as_bytes()])
+}
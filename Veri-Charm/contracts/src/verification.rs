@@ -0,0 +1,333 @@
+//! ZK verification circuit parameters and stateless verification functions
+//!
+//! The free functions here take only data, no contract instance, so
+//! off-chain tools and light clients can run the same checks as the contract.
+
+use super::*;
+use bellman::groth16::{self, PreparedVerifyingKey, Proof, VerifyingKey};
+use bls12_381::{Bls12, Scalar};
+
+/// Field element encoded as 32 big-endian bytes
+pub type Field = [u8; 32];
+
+/// Serialized ZK proof
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ZkProof {
+    pub proof_bytes: Vec<u8>,
+}
+
+/// Verification circuit parameters
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct VerificationCircuit {
+    /// Groth16 verifying key over BLS12-381, in bellman's serialization
+    pub verifying_key: Vec<u8>,
+}
+
+impl VerificationCircuit {
+    /// Verify a Groth16 proof against the given public inputs.
+    ///
+    /// Returns `MalformedProof` if the proof bytes don't decode and
+    /// `InvalidVerifyingKey` if the circuit's key doesn't. Public inputs
+    /// outside the scalar field can't satisfy the circuit and verify false.
+    pub fn verify_proof(
+        &self,
+        proof: &ZkProof,
+        public_inputs: &[Field],
+    ) -> Result<bool, VeriCharmError> {
+        let verifying_key = self.prepared_key()?;
+        let proof = Proof::<Bls12>::read(proof.proof_bytes.as_slice())
+            .map_err(|_| VeriCharmError::MalformedProof)?;
+        
+        let Some(inputs) = public_inputs.iter().map(field_to_scalar).collect::<Option<Vec<_>>>() else {
+            return Ok(false);
+        };
+        Ok(groth16::verify_proof(&verifying_key, &proof, &inputs).is_ok())
+    }
+
+    /// Verify a burn-reason proof that `commitment` opens to `reason`. The
+    /// caller checks the commitment was recorded on-chain.
+    pub fn verify_burn_reason_proof(
+        &self,
+        proof: &ZkProof,
+        commitment: &Field,
+        reason: &BurnReason,
+    ) -> Result<bool, VeriCharmError> {
+        self.verify_proof(proof, &[*commitment, burn_reason::reason_field(reason)])
+    }
+
+    /// Check that the verifying key decodes, before storing the circuit
+    pub fn validate(&self) -> Result<(), VeriCharmError> {
+        self.prepared_key().map(|_| ())
+    }
+
+    fn prepared_key(&self) -> Result<PreparedVerifyingKey<Bls12>, VeriCharmError> {
+        let verifying_key = VerifyingKey::<Bls12>::read(self.verifying_key.as_slice())
+            .map_err(|_| VeriCharmError::InvalidVerifyingKey)?;
+        Ok(groth16::prepare_verifying_key(&verifying_key))
+    }
+
+    /// Identifier for a proof that verified under this circuit, binding the
+    /// key, the product's state and the proof itself. Only meaningful once
+    /// `verify_proof` has accepted `proof`.
+    pub fn attest(&self, product_id: &ProductId, verification_hash: &Hash, proof: &ZkProof) -> Hash {
+        let key_hash: Hash = Sha256::digest(&self.verifying_key).into();
+        crypto::canonical_hash(&[
+            b"veri-charm/manufacturer-attestation",
+            &key_hash,
+            product_id.as_bytes(),
+            verification_hash,
+            &proof.proof_bytes,
+        ])
+    }
+}
+
+/// Ownership challenge for `commitment` over a token's current state:
+/// `sha256(tag || product_id || commitment || verification_hash)`
+pub fn ownership_challenge(product_id: &ProductId, commitment: &Hash, verification_hash: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(b"veri-charm/ownership-challenge");
+    hasher.update(product_id.as_bytes());
+    hasher.update(commitment);
+    hasher.update(verification_hash);
+    hasher.finalize().into()
+}
+
+/// Public stand-in for an owner address in ownership proofs
+pub fn owner_digest(owner: &Address) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(b"veri-charm/owner");
+    hasher.update(owner);
+    hasher.finalize().into()
+}
+
+/// Reduce a hash into the BLS12-381 scalar field by clearing its top three
+/// bits. Raw SHA-256 outputs are often above the field modulus and would be
+/// rejected as public inputs.
+pub fn hash_to_field(hash: &Hash) -> Field {
+    let mut field = *hash;
+    field[0] &= 0x1f;
+    field
+}
+
+/// Scalar for a big-endian field element, if it is below the field modulus
+pub fn field_to_scalar(field: &Field) -> Option<Scalar> {
+    let mut repr = *field;
+    repr.reverse();
+    Option::from(Scalar::from_bytes(&repr))
+}
+
+/// Big-endian field element for a scalar
+pub fn scalar_to_field(scalar: &Scalar) -> Field {
+    let mut field = scalar.to_bytes();
+    field.reverse();
+    field
+}
+
+/// Extend a history root hash chain with `records`
+pub fn extend_history_root(root: Hash, records: &[TransferRecord]) -> Hash {
+    records.iter().fold(root, |root, record| {
+        let mut hasher = Sha256::new();
+        hasher.update(&root);
+        hasher.update(&record.record_hash());
+        hasher.finalize().into()
+    })
+}
+
+/// Whether `prefix` followed by `live` forms a continuous ownership chain
+/// starting at the manufacturer and ending at `current_owner`
+pub fn is_continuous_chain(
+    manufacturer: &Address,
+    current_owner: &Address,
+    prefix: &[TransferRecord],
+    live: &[TransferRecord],
+) -> bool {
+    // Must have at least the mint record
+    if prefix.len() + live.len() < 1 {
+        return false;
+    }
+    
+    // Check for continuous ownership chain; the first transfer must be from
+    // the manufacturer, optionally preceded by the genesis mint record
+    let mut holder = manufacturer;
+    let mut records = prefix.iter().chain(live).peekable();
+    records.next_if(|record| record.is_genesis() && record.to == *manufacturer);
+    
+    for transfer in records {
+        if transfer.from != *holder {
+            return false;
+        }
+        holder = &transfer.to;
+    }
+    
+    // An owner set without a record would otherwise pass on a stale chain
+    holder == current_owner
+}
+
+/// Verify provenance across an archived prefix and live history. The prefix
+/// must hash to `history_root` (zero hash when nothing has been archived).
+pub fn verify_provenance(
+    manufacturer: &Address,
+    current_owner: &Address,
+    history_root: Option<Hash>,
+    archived_transfers: u64,
+    provenance_proof: &[TransferRecord],
+    live: &[TransferRecord],
+) -> Result<bool, VeriCharmError> {
+    let expected_root = history_root.unwrap_or([0u8; 32]);
+    if provenance_proof.len() as u64 != archived_transfers
+        || extend_history_root([0u8; 32], provenance_proof) != expected_root
+    {
+        return Err(VeriCharmError::ProvenanceProofMismatch);
+    }
+    
+    Ok(is_continuous_chain(manufacturer, current_owner, provenance_proof, live))
+}
+
+/// Check a run of audit records: each record's hash must match its contents
+/// and link to the previous record
+pub fn verify_audit_chain(records: &[AuditRecord]) -> bool {
+    records.iter().enumerate().all(|(index, record)| {
+        let linked = index == 0 || record.prev_hash == records[index - 1].record_hash;
+        linked && record.record_hash == record.compute_hash()
+    })
+}
+
+/// Signals combined by `confidence_score`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ConfidenceSignals {
+    pub provenance_continuous: bool,
+    pub zk_proof_valid: bool,
+    pub physical_tag_verified: bool,
+    pub manufacturer_verified: bool,
+    /// Distinct addresses that reported the token as counterfeit
+    pub counterfeit_reports: u32,
+}
+
+/// Provenance confidence from 0 to 100.
+///
+/// Weights: continuous provenance 40, valid ZK proof 20, physical tag 20,
+/// verified manufacturer 20. Each counterfeit report deducts 15, floored at 0.
+pub fn confidence_score(signals: &ConfidenceSignals) -> u8 {
+    let earned = [
+        (signals.provenance_continuous, 40u32),
+        (signals.zk_proof_valid, 20),
+        (signals.physical_tag_verified, 20),
+        (signals.manufacturer_verified, 20),
+    ]
+    .iter()
+    .filter(|(present, _)| *present)
+    .map(|(_, weight)| weight)
+    .sum::<u32>();
+    
+    earned.saturating_sub(signals.counterfeit_reports.saturating_mul(15)) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::address;
+
+    fn hop(from: u8, to: u8, timestamp: u64) -> TransferRecord {
+        TransferRecord {
+            from: address(from),
+            to: address(to),
+            timestamp,
+            tx_hash: [timestamp as u8; 32],
+        }
+    }
+
+    #[test]
+    fn empty_history_is_not_a_chain() {
+        assert!(!is_continuous_chain(&address(1), &address(1), &[], &[]));
+    }
+
+    #[test]
+    fn chain_may_span_the_archived_prefix() {
+        let manufacturer = address(1);
+        let genesis = TransferRecord::genesis(manufacturer.clone(), 0);
+        assert!(is_continuous_chain(&manufacturer, &manufacturer, &[], &[genesis.clone()]));
+        assert!(is_continuous_chain(&manufacturer, &address(3), &[genesis.clone(), hop(1, 2, 1)], &[hop(2, 3, 2)]));
+        assert!(is_continuous_chain(&manufacturer, &address(3), &[], &[hop(1, 2, 1), hop(2, 3, 2)]));
+    }
+
+    #[test]
+    fn broken_chains_are_rejected() {
+        let manufacturer = address(1);
+        let genesis = TransferRecord::genesis(manufacturer.clone(), 0);
+        // Second hop starts from someone who never held the token
+        assert!(!is_continuous_chain(&manufacturer, &address(3), &[genesis.clone()], &[hop(1, 2, 1), hop(4, 3, 2)]));
+        // First hop does not leave the manufacturer
+        assert!(!is_continuous_chain(&manufacturer, &address(3), &[], &[hop(2, 3, 1)]));
+        // Genesis minted to another manufacturer
+        assert!(!is_continuous_chain(&manufacturer, &address(2), &[TransferRecord::genesis(address(9), 0)], &[hop(1, 2, 1)]));
+        // Gap between the archived prefix and live history
+        assert!(!is_continuous_chain(&manufacturer, &address(4), &[genesis, hop(1, 2, 1)], &[hop(3, 4, 2)]));
+    }
+
+    #[test]
+    fn chain_must_end_at_the_current_owner() {
+        let manufacturer = address(1);
+        let history = [TransferRecord::genesis(manufacturer.clone(), 0), hop(1, 2, 1), hop(2, 3, 2)];
+        assert!(is_continuous_chain(&manufacturer, &address(3), &[], &history));
+        // Owner moved on without a record
+        assert!(!is_continuous_chain(&manufacturer, &address(4), &[], &history));
+        // Owner handed back to an earlier holder without a record
+        assert!(!is_continuous_chain(&manufacturer, &address(2), &[], &history));
+        assert!(!is_continuous_chain(&manufacturer, &address(2), &history[..1], &[]));
+    }
+
+    #[test]
+    fn provenance_proof_must_match_the_archived_root_and_count() {
+        let manufacturer = address(1);
+        let owner = address(3);
+        let archived = [TransferRecord::genesis(manufacturer.clone(), 0), hop(1, 2, 1)];
+        let root = extend_history_root([0u8; 32], &archived);
+
+        assert_eq!(verify_provenance(&manufacturer, &owner, Some(root), 2, &archived, &[hop(2, 3, 2)]), Ok(true));
+        assert_eq!(verify_provenance(&manufacturer, &address(2), None, 0, &[], &archived), Ok(true));
+        assert_eq!(verify_provenance(&manufacturer, &address(4), Some(root), 2, &archived, &[hop(3, 4, 2)]), Ok(false));
+        assert_eq!(verify_provenance(&manufacturer, &address(4), Some(root), 2, &archived, &[hop(2, 3, 2)]), Ok(false));
+        assert_eq!(
+            verify_provenance(&manufacturer, &owner, Some(root), 1, &archived[..1], &[hop(2, 3, 2)]),
+            Err(VeriCharmError::ProvenanceProofMismatch),
+        );
+        assert_eq!(
+            verify_provenance(&manufacturer, &address(5), Some(root), 2, &[archived[0].clone(), hop(1, 5, 1)], &[]),
+            Err(VeriCharmError::ProvenanceProofMismatch),
+        );
+    }
+
+    #[test]
+    fn history_root_extends_incrementally() {
+        let records = [hop(1, 2, 1), hop(2, 3, 2), hop(3, 4, 3)];
+        let partial = extend_history_root([0u8; 32], &records[..1]);
+        assert_eq!(extend_history_root(partial, &records[1..]), extend_history_root([0u8; 32], &records));
+        assert_ne!(extend_history_root([0u8; 32], &records[..2]), extend_history_root([0u8; 32], &records));
+    }
+
+    #[test]
+    fn hash_to_field_always_lands_in_the_scalar_field() {
+        let hash = [0xff; 32];
+        assert!(field_to_scalar(&hash).is_none());
+        let field = hash_to_field(&hash);
+        assert_eq!(field[0], 0x1f);
+        assert_eq!(field[1..], hash[1..]);
+        assert!(field_to_scalar(&field).is_some());
+    }
+
+    #[test]
+    fn confidence_weights_and_report_penalty() {
+        let all = ConfidenceSignals {
+            provenance_continuous: true,
+            zk_proof_valid: true,
+            physical_tag_verified: true,
+            manufacturer_verified: true,
+            counterfeit_reports: 0,
+        };
+        assert_eq!(confidence_score(&all), 100);
+        assert_eq!(confidence_score(&ConfidenceSignals { zk_proof_valid: false, ..all.clone() }), 80);
+        assert_eq!(confidence_score(&ConfidenceSignals { counterfeit_reports: 2, ..all.clone() }), 70);
+        assert_eq!(confidence_score(&ConfidenceSignals { counterfeit_reports: 7, ..all }), 0);
+        assert_eq!(confidence_score(&ConfidenceSignals::default()), 0);
+    }
+}